@@ -0,0 +1,204 @@
+use std::env;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+
+use crate::config::{Config, OutputLayout, Rule};
+
+/// Generates bindings from a build script.
+///
+/// Unless [`Builder::output`] is set, the bindings are written to `java_spaghetti.rs` in `OUT_DIR`, to be included
+/// with:
+///
+/// ```ignore
+/// mod bindings {
+///     include!(concat!(env!("OUT_DIR"), "/java_spaghetti.rs"));
+/// }
+/// ```
+///
+/// The `build.rs` would be:
+///
+/// ```no_run
+/// use java_spaghetti_gen::config::{ClassMatch, Rule};
+///
+/// java_spaghetti_gen::Builder::new()
+///     .input("libs/example.jar")
+///     .rule(Rule {
+///         matches: ClassMatch::One("com/example/**".to_string()),
+///         include: Some(true),
+///         ..Default::default()
+///     })
+///     .generate()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Builder {
+    config: Config,
+    output: Option<PathBuf>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from a configuration, e.g. read with [`Config::from_file`]. Its `output` is ignored unless set again
+    /// with [`Builder::output`].
+    pub fn from_config(config: Config) -> Self {
+        Self { config, output: None }
+    }
+
+    /// Adds a class file, archive, JDK `lib/modules` image or directory to read classes from, see `Config::input`.
+    pub fn input(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.input.push(path.into());
+        self
+    }
+
+    /// Adds Java sources or javadoc HTML to copy descriptions from, see `Config::sources`.
+    pub fn source(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.sources.push(path.into());
+        self
+    }
+
+    /// Adds a rule. All classes are included if there are none.
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.config.rules.push(rule);
+        self
+    }
+
+    /// Only reads these modules from `.jmod` and jimage inputs, see `Config::modules`.
+    pub fn module(mut self, module: impl Into<String>) -> Self {
+        self.config.modules.push(module.into());
+        self
+    }
+
+    /// The Java release to pick class versions for from multi-release jars.
+    pub fn java_release(mut self, release: u32) -> Self {
+        self.config.java_release = Some(release);
+        self
+    }
+
+    /// Writes the bindings to `path` instead of `OUT_DIR`, in the layout set by [`Builder::output_layout`].
+    pub fn output(mut self, path: impl Into<PathBuf>) -> Self {
+        self.output = Some(path.into());
+        self
+    }
+
+    /// The layout of the file(s) set by [`Builder::output`]. Output to `OUT_DIR` is always [`OutputLayout::Include`].
+    pub fn output_layout(mut self, layout: OutputLayout) -> Self {
+        self.config.output_layout = layout;
+        self
+    }
+
+    /// Formats the output with an embedded pretty-printer, see `Config::pretty_print`.
+    pub fn pretty_print(mut self, pretty_print: bool) -> Self {
+        self.config.pretty_print = pretty_print;
+        self
+    }
+
+    /// Writes the Java sources of the proxy classes to this directory.
+    pub fn proxy_output(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.proxy_output = Some(dir.into());
+        self
+    }
+
+    /// The Java package of the proxy classes, `java_spaghetti/proxy` by default.
+    pub fn proxy_package(mut self, package: impl Into<String>) -> Self {
+        self.config.proxy_package = package.into();
+        self
+    }
+
+    pub fn degrade_missing_types(mut self, degrade: bool) -> Self {
+        self.config.degrade_missing_types = degrade;
+        self
+    }
+
+    /// Writes the skipped methods, fields and proxies to this file.
+    pub fn report(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.report = Some(path.into());
+        self
+    }
+
+    /// Keeps hashes of the inputs and the generated code in this file, to only generate changed classes again, see
+    /// `Config::cache`.
+    pub fn cache(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.cache = Some(path.into());
+        self
+    }
+
+    /// Generates the bindings, and asks Cargo to run the build script again when an input changes.
+    pub fn generate(self) -> anyhow::Result<()> {
+        self.generate_in(env::var_os("OUT_DIR"), &mut io::stdout())
+    }
+
+    /// Generates the bindings into `out_dir` unless [`Builder::output`] is set, writing the instructions for Cargo
+    /// to `cargo`.
+    fn generate_in(self, out_dir: Option<OsString>, cargo: &mut impl Write) -> anyhow::Result<()> {
+        let mut config = self.config;
+        match self.output {
+            Some(output) => config.output = output,
+            None => {
+                let out_dir = out_dir.context("OUT_DIR is not set, use `Builder::output`")?;
+                config.output = PathBuf::from(out_dir).join("java_spaghetti.rs");
+                config.output_layout = OutputLayout::Include;
+            }
+        }
+        config.add_default_rule();
+
+        for path in config.input.iter().chain(&config.sources) {
+            writeln!(cargo, "cargo:rerun-if-changed={}", path.display())?;
+        }
+        crate::run(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::{ClassFile, TempDir, jdk_classes};
+
+    #[test]
+    fn generate_to_out_dir() {
+        let dir = TempDir::new("builder");
+        let classes = dir.path().join("classes");
+        for class in jdk_classes() {
+            let path = classes.join(format!("{}.class", class.name()));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, class.to_bytes()).unwrap();
+        }
+        let foo = dir.path().join("Foo.class");
+        fs::write(&foo, ClassFile::new("com/example/Foo").to_bytes()).unwrap();
+        let sources = dir.path().join("sources");
+        fs::create_dir(&sources).unwrap();
+        let out_dir = dir.path().join("out");
+        fs::create_dir(&out_dir).unwrap();
+
+        let mut cargo = Vec::new();
+        Builder::new()
+            .input(&classes)
+            .input(&foo)
+            .source(&sources)
+            .generate_in(Some(out_dir.clone().into()), &mut cargo)
+            .unwrap();
+
+        let expected: String = [&classes, &foo, &sources]
+            .map(|path| format!("cargo:rerun-if-changed={}\n", path.display()))
+            .concat();
+        assert_eq!(String::from_utf8(cargo).unwrap(), expected);
+
+        // The file is included with `include!`, which doesn't allow inner attributes.
+        let code = fs::read_to_string(out_dir.join("java_spaghetti.rs")).unwrap();
+        assert!(code.contains("pub enum Foo"));
+        assert!(!code.contains("#!["));
+    }
+
+    #[test]
+    fn generate_without_out_dir() {
+        let err = Builder::new().generate_in(None, &mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("OUT_DIR is not set"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Incremental generation, see `Config::cache`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use std::{fs, io};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::emit::{Class, Context, dependencies_of};
+use crate::parser_util::Id;
+use crate::util::StableHasher;
+
+/// Hashes of the configuration and inputs of a run, and the code generated for each class.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Cache {
+    /// Version of the generator.
+    version: String,
+    /// Hash of the configuration and the files in `Config::sources`, which affect the code of every class.
+    config: u64,
+    /// Hashes of the sizes and modification times of the files in `Config::input` and `Config::sources`.
+    inputs: BTreeMap<PathBuf, u64>,
+    /// Hashes of the class files read from the inputs, by JNI path.
+    class_files: BTreeMap<String, u64>,
+    /// Generated code by JNI path.
+    classes: BTreeMap<String, CachedClass>,
+
+    /// Hash of the paths of all classes, and whether they are generated.
+    #[serde(skip)]
+    known_classes: u64,
+    /// Generated code of the previous run.
+    #[serde(skip)]
+    previous: BTreeMap<String, CachedClass>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedClass {
+    /// Hash of the class files the code depends on, see `Context::cache_key`.
+    key: u64,
+    code: String,
+}
+
+impl Cache {
+    /// Hashes the configuration and the inputs of a run.
+    pub fn new(config: &Config) -> io::Result<Self> {
+        let mut inputs = BTreeMap::new();
+        for path in config.input.iter().chain(&config.sources) {
+            let mut hasher = StableHasher::default();
+            hash_files(path, &mut hasher)?;
+            inputs.insert(path.clone(), hasher.finish());
+        }
+
+        let mut hasher = StableHasher::default();
+        format!("{config:?}").hash(&mut hasher);
+        for path in &config.sources {
+            inputs[path].hash(&mut hasher);
+        }
+
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config: hasher.finish(),
+            inputs,
+            ..Default::default()
+        })
+    }
+
+    /// Reads the cache of a previous run. A missing or unreadable cache is empty.
+    pub fn read(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_vec(self)?)
+    }
+
+    /// Whether the generator, the configuration and the inputs are the same as in the `previous` run.
+    pub fn unchanged(&self, previous: &Cache) -> bool {
+        self.version == previous.version && self.config == previous.config && self.inputs == previous.inputs
+    }
+
+    /// Hashes the classes read from the inputs, keeping the code generated by the `previous` run for reuse.
+    pub fn add_classes(&mut self, context: &Context, previous: Cache) {
+        if previous.version == self.version && previous.config == self.config {
+            self.previous = previous.classes;
+        }
+
+        for (path, class) in &context.all_classes {
+            self.class_files.insert(path.clone(), class.java.content_hash());
+        }
+        for (path, java) in context.reference_classes.iter().chain(&context.excluded_classes) {
+            self.class_files.insert(path.clone(), java.content_hash());
+        }
+
+        let mut known_classes = StableHasher::default();
+        for path in self.class_files.keys() {
+            path.hash(&mut known_classes);
+            context.all_classes.contains_key(path).hash(&mut known_classes);
+        }
+        self.known_classes = known_classes.finish();
+    }
+}
+
+impl Context<'_> {
+    /// Returns the code of a class made by `write`, or the code of the previous run if none of the class files it
+    /// depends on changed. Classes are always written again if `Config::report` is set, to report their skipped
+    /// members.
+    pub(crate) fn cached_class(
+        &self,
+        class: &Class,
+        write: impl FnOnce() -> anyhow::Result<String>,
+    ) -> anyhow::Result<String> {
+        let Some(cache) = &self.cache else {
+            return write();
+        };
+        let path = class.java.path().as_str();
+        let key = self.cache_key(class, &cache.lock().unwrap());
+
+        let previous = match self.config.report {
+            None => cache
+                .lock()
+                .unwrap()
+                .previous
+                .remove(path)
+                .filter(|cached| cached.key == key),
+            Some(_) => None,
+        };
+        let code = match previous {
+            Some(cached) => cached.code,
+            None => write()?,
+        };
+        let cached = CachedClass {
+            key,
+            code: code.clone(),
+        };
+        cache.lock().unwrap().classes.insert(path.to_string(), cached);
+        Ok(code)
+    }
+
+    /// Hashes the class files the code of a class depends on: the class, its outer classes, its superclasses and
+    /// interfaces, and the classes in its signatures with their superclasses and interfaces. Signatures also depend
+    /// on which classes are known and generated, and any class may be named in type arguments.
+    fn cache_key(&self, class: &Class, cache: &Cache) -> u64 {
+        let path = class.java.path().as_str();
+        let mut related = BTreeSet::new();
+        let mut outer = path;
+        while let Some((prefix, _)) = outer.rsplit_once('$') {
+            related.insert(prefix.to_string());
+            outer = prefix;
+        }
+        self.add_supertypes(path, &mut related);
+        let cc = self.config.resolve_class(path);
+        for (dependency, _) in dependencies_of(&class.java, &cc) {
+            self.add_supertypes(&dependency, &mut related);
+        }
+
+        let mut hasher = StableHasher::default();
+        cache.config.hash(&mut hasher);
+        cache.known_classes.hash(&mut hasher);
+        for path in &related {
+            path.hash(&mut hasher);
+            cache.class_files.get(path).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Adds a class and its superclasses and interfaces, recursively.
+    fn add_supertypes(&self, path: &str, related: &mut BTreeSet<String>) {
+        if !related.insert(path.to_string()) {
+            return;
+        }
+        let Some(java) = self.java_class(path) else {
+            return;
+        };
+        let supertypes: Vec<String> = (java.super_path().into_iter())
+            .chain(java.interfaces().map(Id::from))
+            .map(|path| path.as_str().to_string())
+            .collect();
+        for supertype in supertypes {
+            self.add_supertypes(&supertype, related);
+        }
+    }
+}
+
+/// Hashes the sizes and modification times of a file, or of the files in a directory, recursively. A file rewritten
+/// with the same size within the timestamp resolution of the file system (a second on some) is not noticed.
+fn hash_files(path: &Path, hasher: &mut StableHasher) -> io::Result<()> {
+    let metadata = fs::metadata(path)?;
+    if metadata.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+        for entry in entries {
+            entry.file_name().hash(hasher);
+            hash_files(&entry, hasher)?;
+        }
+        return Ok(());
+    }
+    metadata.len().hash(hasher);
+    let modified = metadata.modified()?.duration_since(UNIX_EPOCH).unwrap_or_default();
+    modified.hash(hasher);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{ACC_PUBLIC, ClassFile, Member, TempDir, jdk_classes};
+
+    #[test]
+    fn stable_hasher() {
+        let mut hasher = StableHasher::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    fn write_classes(dir: &Path, classes: &[ClassFile]) {
+        for class in classes {
+            let path = dir.join("classes").join(format!("{}.class", class.name()));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, class.to_bytes()).unwrap();
+        }
+    }
+
+    fn setup(name: &str) -> (TempDir, Config) {
+        let dir = TempDir::new(name);
+        let mut classes = jdk_classes();
+        classes.push(ClassFile::new("com/example/Foo"));
+        classes.push(ClassFile::new("com/example/Bar"));
+        write_classes(dir.path(), &classes);
+        let yaml = "input: [classes]\noutput: out.rs\ncache: cache.json\n";
+        let config = Config::read_str(yaml, dir.path()).unwrap();
+        (dir, config)
+    }
+
+    #[test]
+    fn unchanged_run() {
+        let (dir, config) = setup("cache-unchanged");
+        crate::run(config.clone()).unwrap();
+        assert!(
+            Cache::new(&config)
+                .unwrap()
+                .unchanged(&Cache::read(&dir.path().join("cache.json")))
+        );
+
+        // The output isn't written again, even if it was edited.
+        let out = dir.path().join("out.rs");
+        let marker = fs::read_to_string(&out).unwrap().lines().next().unwrap().to_string();
+        let edited = format!("{marker}\n// edited\n");
+        fs::write(&out, &edited).unwrap();
+        crate::run(config.clone()).unwrap();
+        assert_eq!(fs::read_to_string(&out).unwrap(), edited);
+
+        // Missing outputs are generated again.
+        fs::remove_file(&out).unwrap();
+        crate::run(config).unwrap();
+        assert!(fs::read_to_string(&out).unwrap().contains("pub enum Foo"));
+    }
+
+    #[test]
+    fn partial_rebuild() {
+        let (dir, config) = setup("cache-partial");
+        crate::run(config.clone()).unwrap();
+
+        // Replaces the cached code, to tell reused code apart from generated code.
+        let cache_path = dir.path().join("cache.json");
+        let mut cache: serde_json::Value = serde_json::from_slice(&fs::read(&cache_path).unwrap()).unwrap();
+        for class in ["Foo", "Bar"] {
+            cache["classes"][format!("com/example/{class}")]["code"] = format!("// cached {class}\n").into();
+        }
+        fs::write(&cache_path, serde_json::to_vec(&cache).unwrap()).unwrap();
+
+        let foo = ClassFile::new("com/example/Foo").method(Member::new(ACC_PUBLIC, "size", "()I"));
+        write_classes(dir.path(), &[foo]);
+        crate::run(config).unwrap();
+
+        let code = fs::read_to_string(dir.path().join("out.rs")).unwrap();
+        assert!(code.contains("// cached Bar"));
+        assert!(!code.contains("// cached Foo"));
+        assert!(code.contains("pub fn size"));
+    }
+}
@@ -5,10 +5,17 @@ use std::{fs, io};
 
 use serde_derive::Deserialize;
 
+pub use crate::identifiers::NamingConvention;
+use crate::parser_util::{JavaField, JavaMethod};
+
 fn default_proxy_package() -> String {
     "java_spaghetti/proxy".to_string()
 }
 
+fn default_reference_fallback() -> String {
+    "java/lang/Object".to_string()
+}
+
 fn default_slash() -> String {
     String::from("/")
 }
@@ -90,6 +97,22 @@ pub struct DocPattern {
     pub argument_seperator: String,
 }
 
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputLayout {
+    /// Everything in the `output` file.
+    #[default]
+    SingleFile,
+    /// A directory tree with a `mod.rs` per Rust module, holding the classes of its Java package. Include it with
+    /// `#[path = ".../mod.rs"] mod bindings;`.
+    PerPackage,
+    /// A single file without inner attributes, for `mod bindings { include!(...); }`. This is what [`Builder`]
+    /// writes to `OUT_DIR`, since `#[path]` can't refer to it.
+    ///
+    /// [`Builder`]: crate::Builder
+    Include,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum ClassMatch {
@@ -153,8 +176,104 @@ pub struct Rule {
     #[serde(default)]
     pub proxy: Option<bool>,
 
+    /// Matched classes that are not included are still parsed, so that they are known for type resolution and
+    /// inheritance. Signatures using them refer to `Config::reference_fallback` instead, or to their nearest
+    /// generated superclass if `Config::degrade_missing_types` is enabled.
+    #[serde(default)]
+    pub reference_only: Option<bool>,
+
+    /// Also includes classes referenced by matched classes as superclasses, interfaces, field types or in
+    /// method signatures, recursively up to the given depth. Candidates are classes from the inputs that are
+    /// not included otherwise, including reference-only classes.
+    #[serde(default)]
+    pub include_dependencies: Option<u32>,
+
     #[serde(default)]
     pub doc_pattern: Option<DocPattern>,
+
+    /// Generates the type parameters of generic classes and methods from their `Signature` attributes, e.g.
+    /// `List<E>` with `fn get(&self, index: i32) -> Option<Local<E>>`. Off by default, since constructors of generic
+    /// classes then need type arguments, e.g. `ArrayList::<String>::new(env)` or a type annotation.
+    #[serde(default)]
+    pub generics: Option<bool>,
+
+    /// Also generates a method for each set of overloads, taking the arguments as a tuple whose types pick the
+    /// overload at compile time, e.g. `sb.append((&string,))` for `sb.append_string(&string)`.
+    #[serde(default)]
+    pub generic_overloads: Option<bool>,
+
+    /// Also generates the public methods of superclasses and superinterfaces on the matched classes, so they can be
+    /// called without upcasting, e.g. `activity.toString()`.
+    #[serde(default)]
+    pub inherited_methods: Option<bool>,
+
+    /// Implements `JavaDisplay` for the matched classes with `toString()`, so that references can be formatted with
+    /// `{}`. Formatting panics if `toString()` throws an exception.
+    #[serde(default)]
+    pub java_display: Option<bool>,
+
+    /// Implements `JavaEq` for the matched classes, and `JavaOrd` for those comparable to themselves, so that
+    /// `JavaKey` compares, hashes and orders them with `equals()`, `hashCode()` and `compareTo()`.
+    #[serde(default)]
+    pub java_eq: Option<bool>,
+
+    /// Generates the instance methods of Kotlin `object` declarations and companion objects as associated functions
+    /// reading the instance, e.g. `Registry::count(env)` instead of `Registry::INSTANCE(env)?.count()`.
+    #[serde(default)]
+    pub kotlin_objects: Option<bool>,
+
+    /// How methods, field accessors and constants of the matched classes are named, `java` by default.
+    #[serde(default)]
+    pub naming: Option<NamingConvention>,
+
+    /// Overrides for methods of the matched classes.
+    #[serde(default)]
+    pub methods: Vec<MemberRule>,
+
+    /// Overrides for fields of the matched classes.
+    #[serde(default)]
+    pub fields: Vec<MemberRule>,
+}
+
+/// Overrides for a method or field, applied before method names are disambiguated with their signatures.
+///
+/// ```yaml
+/// methods:
+///   - name: "<init>"
+///     descriptor: "(II)V"
+///     rename: new_with_range
+///   - name: getBytes
+///     descriptor: "(Ljava/lang/String;)[B"
+///     include: false
+/// ```
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct MemberRule {
+    /// The Java name, `<init>` for constructors.
+    pub name: String,
+
+    /// The JNI descriptor, e.g. `(II)Ljava/lang/String;` or `I`. Matches all overloads if unset.
+    #[serde(default)]
+    pub descriptor: Option<String>,
+
+    /// Excludes the member, or includes it even if it is not public.
+    #[serde(default)]
+    pub include: Option<bool>,
+
+    /// The Rust name of the method, constant or field getter, regardless of `Rule::naming`. Field setters are named
+    /// `set_{rename}`. Overloads renamed by a rule without `descriptor` get their signatures appended, e.g.
+    /// `{rename}_int`, like other colliding methods.
+    #[serde(default)]
+    pub rename: Option<String>,
+
+    /// Excludes the method from the proxy of its class, see `Rule::proxy`.
+    #[serde(default)]
+    pub proxy: Option<bool>,
+}
+
+impl MemberRule {
+    fn matches(&self, name: &str, descriptor: &str) -> bool {
+        self.name == name && self.descriptor.as_deref().is_none_or(|d| d == descriptor)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -165,14 +284,95 @@ pub struct ClassConfig<'a> {
     pub include_private_methods: bool,
     pub include_private_fields: bool,
     pub proxy: bool,
+    pub reference_only: bool,
+    pub include_dependencies: u32,
     pub doc_pattern: Option<&'a DocPattern>,
+    pub generics: bool,
+    pub generic_overloads: bool,
+    pub inherited_methods: bool,
+    pub java_display: bool,
+    pub java_eq: bool,
+    pub kotlin_objects: bool,
+    pub naming: NamingConvention,
+    pub methods: Vec<&'a MemberRule>,
+    pub fields: Vec<&'a MemberRule>,
+}
+
+/// The merged `MemberRule`s of a method or field.
+#[derive(Debug, Clone, Default)]
+pub struct MemberConfig<'a> {
+    pub include: Option<bool>,
+    pub rename: Option<&'a str>,
+    pub proxy: Option<bool>,
+}
+
+impl<'a> ClassConfig<'a> {
+    pub fn method(&self, name: &str, descriptor: &str) -> MemberConfig<'a> {
+        Self::member(&self.methods, name, descriptor)
+    }
+
+    pub fn field(&self, name: &str, descriptor: &str) -> MemberConfig<'a> {
+        Self::member(&self.fields, name, descriptor)
+    }
+
+    /// Whether the method is generated: included by a `MemberRule`, or public unless excluded by one (private
+    /// methods too with `include_private_methods`). Bridge methods never are.
+    pub fn includes_method(&self, method: &JavaMethod) -> bool {
+        let member = self.method(method.name(), &method.descriptor().to_string());
+        let public = method.is_public() || self.include_private_methods;
+        member.include.unwrap_or(public) && !method.is_bridge()
+    }
+
+    /// Whether the field is generated, see [Self::includes_method].
+    pub fn includes_field(&self, field: &JavaField) -> bool {
+        let member = self.field(field.name(), &field.descriptor().to_string());
+        member
+            .include
+            .unwrap_or(field.is_public() || self.include_private_fields)
+    }
+
+    fn member(rules: &[&'a MemberRule], name: &str, descriptor: &str) -> MemberConfig<'a> {
+        let mut res = MemberConfig::default();
+        for rule in rules.iter().filter(|rule| rule.matches(name, descriptor)) {
+            if let Some(include) = rule.include {
+                res.include = Some(include);
+            }
+            if let Some(rename) = &rule.rename {
+                res.rename = Some(rename);
+            }
+            if let Some(proxy) = rule.proxy {
+                res.proxy = Some(proxy);
+            }
+        }
+        res
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
+    /// Class files, archives (`.jar`, `.aar`, `.jmod`), JDK `lib/modules` images or directories to read classes from.
+    /// Directories are walked recursively for `.class` files.
     pub input: Vec<PathBuf>,
+    /// The generated `.rs` file, or the directory to generate into with `OutputLayout::PerPackage`.
     pub output: PathBuf,
 
+    #[serde(default)]
+    pub output_layout: OutputLayout,
+
+    /// Formats the output with an embedded pretty-printer, so that it is readable without running `rustfmt`.
+    /// Otherwise lines are only broken after `;` and braces, which is faster.
+    #[serde(default)]
+    pub pretty_print: bool,
+
+    /// Also read `.jar` and `.aar` files found while walking input directories.
+    #[serde(default)]
+    pub read_jars_in_directories: bool,
+
+    /// Java sources (`-sources.jar` files or directories) or javadoc HTML (javadoc `.jar` files or directories) to
+    /// copy the first paragraph of the descriptions of classes, methods and fields from.
+    #[serde(default)]
+    pub sources: Vec<PathBuf>,
+
     #[serde(default = "default_proxy_package")]
     pub proxy_package: String,
     #[serde(default)]
@@ -181,8 +381,63 @@ pub struct Config {
     #[serde(default)]
     pub logging_verbose: bool,
 
+    /// Writes the methods, fields and proxies skipped by the generator, with the reasons, to this file, followed by
+    /// the classes that are not generated and the classes included by `Rule::include_dependencies`, and why.
+    /// The report is JSON if the file has a `.json` extension, otherwise plain text.
+    #[serde(default)]
+    pub report: Option<PathBuf>,
+
+    /// Stores hashes of the configuration and inputs, and the generated code of each class, in this file. Runs with
+    /// unchanged inputs then return right away, and only classes depending on changed class files are generated again.
+    /// Inputs are compared by size and modification time, so a file rewritten with the same size within the timestamp
+    /// resolution of the file system is not noticed; delete the cache to generate everything again.
+    #[serde(default)]
+    pub cache: Option<PathBuf>,
+
+    /// The Java release (e.g. `11`) to pick class versions for from multi-release jars.
+    /// Versioned entries under `META-INF/versions/` are ignored if unset.
+    #[serde(default)]
+    pub java_release: Option<u32>,
+
+    /// Java modules to read from `.jmod` and jimage (`lib/modules`) inputs, e.g. `java.base`. Reads all if empty.
+    #[serde(default)]
+    pub modules: Vec<String>,
+
     #[serde(default)]
     pub rules: Vec<Rule>,
+
+    /// The class used in generated signatures in place of reference-only classes (see `Rule::reference_only`).
+    #[serde(default = "default_reference_fallback")]
+    pub reference_fallback: String,
+
+    /// Replace classes that are not generated with their nearest generated superclass in signatures, falling back
+    /// to `reference_fallback` (`java/lang/Object` by default), instead of skipping methods and fields using them.
+    /// Replaced argument types are checked at runtime, returning an `IllegalArgumentException` for other objects.
+    #[serde(default)]
+    pub degrade_missing_types: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            input: Vec::new(),
+            output: PathBuf::new(),
+            output_layout: OutputLayout::default(),
+            pretty_print: false,
+            read_jars_in_directories: false,
+            sources: Vec::new(),
+            proxy_package: default_proxy_package(),
+            proxy_output: None,
+            logging_verbose: false,
+            report: None,
+            cache: None,
+            java_release: None,
+            modules: Vec::new(),
+            rules: Vec::new(),
+            reference_fallback: default_reference_fallback(),
+            degrade_missing_types: false,
+        }
+    }
 }
 
 impl Config {
@@ -199,29 +454,41 @@ impl Config {
     pub fn read_str(buffer: &str, dir: &Path) -> io::Result<Self> {
         let mut config: Config =
             serde_yaml::from_str(buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        if config.rules.is_empty() {
-            config.rules.push(Rule {
-                matches: ClassMatch::default(),
-                include: Some(true),
-                ..Default::default()
-            })
-        }
+        config.add_default_rule();
 
         config.output = resolve_file(&config.output, dir);
         if let Some(proxy_output) = &mut config.proxy_output {
             *proxy_output = resolve_file(proxy_output, dir);
         }
+        if let Some(report) = &mut config.report {
+            *report = resolve_file(report, dir);
+        }
+        if let Some(cache) = &mut config.cache {
+            *cache = resolve_file(cache, dir);
+        }
         for f in &mut config.input {
             *f = resolve_file(f, dir);
         }
+        for f in &mut config.sources {
+            *f = resolve_file(f, dir);
+        }
 
         Ok(config)
     }
 
+    /// Includes all classes if there are no rules.
+    pub(crate) fn add_default_rule(&mut self) {
+        if self.rules.is_empty() {
+            self.rules.push(Rule {
+                matches: ClassMatch::default(),
+                include: Some(true),
+                ..Default::default()
+            })
+        }
+    }
+
     /// Search the current directory - or failing that, it's ancestors - until we find "java-spaghetti.yaml" or reach the
     /// root of the filesystem and cannot continue.
-    #[allow(dead_code)]
     pub fn from_current_directory() -> io::Result<Self> {
         Self::from_directory(std::env::current_dir()?.as_path())
     }
@@ -256,6 +523,15 @@ impl Config {
         Self::read(&mut file, config_dir)
     }
 
+    /// Returns true if any rule has `include_dependencies` enabled.
+    pub fn includes_dependencies(&self) -> bool {
+        self.rules.iter().any(|r| r.include_dependencies.unwrap_or(0) > 0)
+    }
+
+    pub fn includes_module(&self, module: &str) -> bool {
+        self.modules.is_empty() || self.modules.iter().any(|m| m == module)
+    }
+
     pub fn resolve_class(&self, class: &str) -> ClassConfig<'_> {
         let mut res = ClassConfig {
             include: false,
@@ -263,7 +539,18 @@ impl Config {
             include_private_methods: false,
             include_private_fields: false,
             proxy: false,
+            reference_only: false,
+            include_dependencies: 0,
             doc_pattern: None,
+            generics: false,
+            generic_overloads: false,
+            inherited_methods: false,
+            java_display: false,
+            java_eq: false,
+            kotlin_objects: false,
+            naming: NamingConvention::Java,
+            methods: Vec::new(),
+            fields: Vec::new(),
         };
 
         for r in &self.rules {
@@ -283,9 +570,38 @@ impl Config {
                 if let Some(proxy) = r.proxy {
                     res.proxy = proxy;
                 }
+                if let Some(reference_only) = r.reference_only {
+                    res.reference_only = reference_only;
+                }
+                if let Some(include_dependencies) = r.include_dependencies {
+                    res.include_dependencies = include_dependencies;
+                }
                 if let Some(doc_pattern) = &r.doc_pattern {
                     res.doc_pattern = Some(doc_pattern);
                 }
+                if let Some(generics) = r.generics {
+                    res.generics = generics;
+                }
+                if let Some(generic_overloads) = r.generic_overloads {
+                    res.generic_overloads = generic_overloads;
+                }
+                if let Some(inherited_methods) = r.inherited_methods {
+                    res.inherited_methods = inherited_methods;
+                }
+                if let Some(java_display) = r.java_display {
+                    res.java_display = java_display;
+                }
+                if let Some(java_eq) = r.java_eq {
+                    res.java_eq = java_eq;
+                }
+                if let Some(kotlin_objects) = r.kotlin_objects {
+                    res.kotlin_objects = kotlin_objects;
+                }
+                if let Some(naming) = r.naming {
+                    res.naming = naming;
+                }
+                res.methods.extend(&r.methods);
+                res.fields.extend(&r.fields);
             }
         }
 
@@ -468,4 +784,45 @@ mod tests {
         assert!(match_single_dir.matches("com/example/MyClass"));
         assert!(!match_single_dir.matches("com/deep/nested/MyClass")); // single * doesn't cross /
     }
+
+    #[test]
+    fn test_member_rules() {
+        let yaml = r#"
+input: []
+output: out.rs
+rules:
+  - match: "com/example/**"
+    include: true
+    methods:
+      - name: get
+        rename: get_any
+      - name: get
+        descriptor: "(I)Ljava/lang/Object;"
+        include: false
+  - match: "com/example/Foo"
+    methods:
+      - name: get
+        rename: get_foo
+        proxy: false
+    fields:
+      - name: SIZE
+        rename: size
+"#;
+        let config = Config::read_str(yaml, Path::new("/")).unwrap();
+
+        let cc = config.resolve_class("com/example/Foo");
+        let get = cc.method("get", "()Ljava/lang/Object;");
+        assert_eq!(
+            (get.include, get.rename, get.proxy),
+            (None, Some("get_foo"), Some(false))
+        );
+        let get_int = cc.method("get", "(I)Ljava/lang/Object;");
+        assert_eq!((get_int.include, get_int.rename), (Some(false), Some("get_foo")));
+        assert_eq!(cc.field("SIZE", "I").rename, Some("size"));
+        assert_eq!(cc.field("size", "I").rename, None);
+
+        let cc = config.resolve_class("com/example/Bar");
+        assert_eq!(cc.method("get", "()Ljava/lang/Object;").rename, Some("get_any"));
+        assert!(cc.fields.is_empty());
+    }
 }
@@ -10,10 +10,12 @@ use super::fields::RustTypeFlavor;
 use super::methods::Method;
 use crate::emit::Context;
 use crate::emit::fields::emit_type;
+use crate::util::Rejection;
 
 impl Class {
     #[allow(clippy::vec_init_then_push)]
     pub(crate) fn write_proxy(&self, context: &Context, methods: &[Method]) -> anyhow::Result<TokenStream> {
+        let cc = context.config.resolve_class(self.java.path().as_str());
         let mut emit_reject_reasons = Vec::new();
 
         let mut out = TokenStream::new();
@@ -35,7 +37,8 @@ impl Class {
         let mut native_regs = Vec::new();
         for method in methods {
             let Some(rust_name) = method.rust_name() else { continue };
-            if method.java.is_static()
+            if !method.is_proxied(&cc)
+                || method.java.is_static()
                 || method.java.is_static_init()
                 || method.java.is_constructor()
                 || method.java.is_final()
@@ -81,8 +84,9 @@ impl Class {
             let mut native_args = TokenStream::new();
             let mut native_convert_args = TokenStream::new();
 
-            for (arg_idx, arg) in method.java.descriptor.parameters.iter().enumerate() {
-                let arg_name = format_ident!("arg{}", arg_idx);
+            let arg_names = method.rust_arg_names();
+            for (arg, arg_name) in method.java.descriptor.parameters.iter().zip(&arg_names) {
+                let arg_name = format_ident!("{arg_name}");
 
                 let trait_arg_type = emit_type(
                     arg,
@@ -228,7 +232,17 @@ impl Class {
         out.extend(quote!(impl #rust_name { #contents }));
 
         if !emit_reject_reasons.is_empty() {
-            // TODO log
+            let class = self.java.path().as_str();
+            context.report.lock().unwrap().reject(
+                class,
+                Rejection {
+                    kind: "proxy",
+                    name: format!("{}Proxy", self.rust.struct_name),
+                    descriptor: String::new(),
+                    signature: format!("proxy of {}", class.replace(['/', '$'], ".")),
+                    reasons: emit_reject_reasons,
+                },
+            );
             return Ok(TokenStream::new());
         }
 
@@ -1,16 +1,24 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
 use super::cstring;
-use super::fields::Field;
+use super::enums::ENUM_METHODS;
+use super::fields::{Field, emit_notes};
+use super::generics::{TypeEmitter, TypeScope, emit_impl_generics};
 use super::known_docs_url::KnownDocsUrl;
-use super::methods::Method;
+use super::methods::{KotlinSingleton, Method};
+use super::overloads::ClassGenerics;
+use super::preamble::reserved_names;
+use crate::config::ClassConfig;
 use crate::emit::Context;
-use crate::identifiers::{FieldMangling, rust_ident};
-use crate::parser_util::{Id, IdPart, JavaClass};
+use crate::identifiers::{FieldMangling, MethodManglingStyle, NamingConvention, rust_ident};
+use crate::parser_util::{
+    ClassTypeSignature, Id, IdPart, JavaClass, KotlinClassKind, KotlinMetadata, Nullability, TypeArgument,
+    TypeSignature,
+};
 
 #[derive(Debug, Default)]
 pub(crate) struct StructPaths {
@@ -31,6 +39,10 @@ impl StructPaths {
 pub(crate) struct Class {
     pub rust: StructPaths,
     pub java: JavaClass,
+    /// Java names of the type parameters of the class, empty unless `Rule::generics` is enabled. See
+    /// `Context::class_type_params`.
+    pub type_params: Vec<String>,
+    pub kotlin: Option<KotlinMetadata>,
 }
 
 impl Class {
@@ -63,10 +75,20 @@ impl Class {
         Ok(buf)
     }
 
-    pub(crate) fn new(java: JavaClass) -> Result<Self, anyhow::Error> {
+    pub(crate) fn new(java: JavaClass, generics: bool) -> Result<Self, anyhow::Error> {
         let rust = StructPaths::new(java.path())?;
+        let type_params = (java.signature())
+            .filter(|_| generics)
+            .map(|sig| sig.params)
+            .unwrap_or_default();
+        let kotlin = KotlinMetadata::read(&java);
 
-        Ok(Self { rust, java })
+        Ok(Self {
+            rust,
+            java,
+            type_params,
+            kotlin,
+        })
     }
 
     pub(crate) fn write(&self, context: &Context) -> anyhow::Result<TokenStream> {
@@ -100,6 +122,8 @@ impl Class {
             Some(url) => format!("{keyword} {url}"),
             None => format!("{keyword} {}", self.java.path().as_str()),
         };
+        let javadoc = context.javadoc.class(self.java.path().as_str());
+        let javadoc = emit_notes(&Vec::from_iter(javadoc.map(|doc| doc.render(context, &self.rust.mod_))));
 
         let rust_name = format_ident!("{}", &self.rust.struct_name);
 
@@ -107,12 +131,37 @@ impl Class {
 
         let java_path = cstring(self.java.path().as_str());
 
-        out.extend(quote!(
-            #[doc = #docs]
-            #attributes
-            #visibility enum #rust_name {}
+        let mut class_scope = TypeScope::default();
+        let type_params = class_scope.add(
+            context.class_type_params(self.java.path().as_str()),
+            context,
+            &self.rust.mod_,
+        )?;
+        let impl_generics = emit_impl_generics(&type_params);
+        let self_type = if type_params.is_empty() {
+            out.extend(quote!(
+                #[doc = #docs]
+                #javadoc
+                #attributes
+                #visibility enum #rust_name {}
+            ));
+            quote!(#rust_name)
+        } else {
+            let object = context.java_to_rust_path(Id("java/lang/Object"), &self.rust.mod_)?;
+            out.extend(quote!(
+                #[doc = #docs]
+                #javadoc
+                #attributes
+                #visibility struct #rust_name<#(#type_params = #object),*>(
+                    ::std::convert::Infallible,
+                    ::std::marker::PhantomData<(#(#type_params,)*)>,
+                );
+            ));
+            quote!(#rust_name<#(#type_params),*>)
+        };
 
-            unsafe impl ::java_spaghetti::ReferenceType for #rust_name {
+        out.extend(quote!(
+            unsafe impl #impl_generics ::java_spaghetti::ReferenceType for #self_type {
                 fn jni_reference_type_name() -> ::std::borrow::Cow<'static, ::std::ffi::CStr> {
                     ::std::borrow::Cow::Borrowed(#java_path)
                 }
@@ -123,66 +172,249 @@ impl Class {
             }
         ));
 
-        // recursively visit all superclasses and superinterfaces.
-        let mut queue = Vec::new();
+        // recursively visit all superclasses and superinterfaces, passing through reference-only classes.
+        // Type arguments are substituted along the way, and superclasses reached through raw types are assignable
+        // with any type arguments.
+        let mut queue = vec![ClassTypeSignature {
+            path: self.java.path().as_str().to_string(),
+            args: self
+                .type_params
+                .iter()
+                .map(|param| TypeArgument::Exact(TypeSignature::TypeVariable(param.clone())))
+                .collect(),
+        }];
         let mut visited = HashSet::new();
-        queue.push(self.java.path());
-        visited.insert(self.java.path());
-        while let Some(path) = queue.pop() {
-            let class = context.all_classes.get(path.as_str()).unwrap();
-            for path2 in self.java.interfaces().map(|i| Id(i)).chain(class.java.super_path()) {
-                if context.all_classes.contains_key(path2.as_str()) && !visited.contains(&path2) {
-                    let rust_path = context.java_to_rust_path(path2, &self.rust.mod_).unwrap();
-                    out.extend(quote!(
-                        unsafe impl ::java_spaghetti::AssignableTo<#rust_path> for #rust_name {}
-                    ));
-                    queue.push(path2);
-                    visited.insert(path2);
+        visited.insert(self.java.path().as_str().to_string());
+        while let Some(current) = queue.pop() {
+            let class = context.java_class(&current.path).unwrap();
+            for super_class in super_classes(class, &current) {
+                if context.java_class(&super_class.path).is_some() && !visited.contains(&super_class.path) {
+                    if context.all_classes.contains_key(&super_class.path) {
+                        let mut types = TypeEmitter::new(context, &class_scope, &self.rust.mod_);
+                        let rust_type = types.emit_class(&super_class);
+                        let impl_generics = emit_impl_generics(type_params.iter().chain(&types.fresh));
+                        out.extend(quote!(
+                            unsafe impl #impl_generics ::java_spaghetti::AssignableTo<#rust_type> for #self_type {}
+                        ));
+                    }
+                    visited.insert(super_class.path.clone());
+                    queue.push(super_class);
                 }
             }
         }
 
         let mut contents = TokenStream::new();
 
-        let mut methods: Vec<Method> = self
-            .java
-            .methods()
-            .map(|m| Method::new(&self.java, m))
-            .filter(|m| (m.java.is_public() || cc.include_private_methods) && !m.java.is_bridge())
-            .collect();
-        let mut fields: Vec<Field> = self
-            .java
-            .fields()
-            .map(|f| Field::new(&self.java, f))
-            .filter(|f| f.java.is_public() || cc.include_private_fields)
-            .collect();
+        let (methods, mut fields, declared) = self.resolved_members(context, &cc)?;
+
+        // Static members of generic classes go to a separate impl for the default type arguments, so that they
+        // can be called without specifying type arguments.
+        let mut static_contents = TokenStream::new();
+
+        let mut emitted = Vec::new();
+        for method in &methods {
+            let res = method.emit(context, &cc, &class_scope, &self.rust.mod_).unwrap();
+            if !res.is_empty() {
+                emitted.push(method);
+            }
+            match method.java.is_static() && !type_params.is_empty() {
+                true => static_contents.extend(res),
+                false => contents.extend(res),
+            }
+        }
+
+        // Varargs methods also take the trailing arguments without building an array, unless the name is taken.
+        let mut taken: HashSet<String> = self.fill_name_counts(&methods, &fields).into_keys().collect();
+        for method in emitted.iter().filter(|method| method.java.is_varargs()) {
+            let name = format!("{}_varargs", method.rust_name().unwrap().trim_start_matches("r#"));
+            if !taken.insert(name.clone()) {
+                continue;
+            }
+            let res = method.emit_varargs(context, &class_scope, &self.rust.mod_, &name)?;
+            match method.java.is_static() && !type_params.is_empty() {
+                true => static_contents.extend(res),
+                false => contents.extend(res),
+            }
+        }
 
-        self.resolve_collisions(&mut methods, &fields)?;
+        let class = ClassGenerics {
+            self_type: &self_type,
+            type_params: &type_params,
+            scope: &class_scope,
+        };
+        out.extend(self.write_interface_trait(context, &cc, &class, &emitted)?);
+        out.extend(self.write_java_traits(context, &cc, &class));
+        if cc.generic_overloads {
+            let overloads = self.write_overloads(context, &cc, &class, &emitted, taken)?;
+            out.extend(overloads.traits);
+            contents.extend(overloads.methods);
+            static_contents.extend(overloads.static_methods);
+        }
 
-        for method in &mut methods {
-            let res = method.emit(context, &cc, &self.rust.mod_).unwrap();
-            contents.extend(res);
+        if let Some(conversions) = self.write_enum(context, &cc) {
+            out.extend(conversions.enum_);
+            contents.extend(conversions.methods);
         }
 
         for field in &mut fields {
-            let res = field.emit(context, &cc, &self.rust.mod_).unwrap();
-            contents.extend(res);
+            let res = field.emit(context, &cc, &class_scope, &self.rust.mod_).unwrap();
+            match field.java.is_static() && !type_params.is_empty() {
+                true => static_contents.extend(res),
+                false => contents.extend(res),
+            }
         }
 
-        out.extend(quote!(impl #rust_name { #contents }));
+        out.extend(quote!(impl #impl_generics #self_type { #contents }));
+        if !static_contents.is_empty() {
+            out.extend(quote!(impl #rust_name { #static_contents }));
+        }
 
         if cc.proxy {
-            out.extend(self.write_proxy(context, &methods)?);
+            out.extend(self.write_proxy(context, &methods[..declared])?);
         }
 
         Ok(out)
     }
 
-    /// Fills the name_counts map with all field and method names
+    /// Returns the methods and fields of the class with their Rust names, followed by the inherited methods with
+    /// `Rule::inherited_methods`, and the number of declared methods.
+    fn resolved_members<'a>(
+        &'a self,
+        context: &'a Context,
+        cc: &ClassConfig,
+    ) -> anyhow::Result<(Vec<Method<'a>>, Vec<Field<'a>>, usize)> {
+        let (mut methods, mut fields) = self.members(context, cc);
+        let declared = methods.len();
+        if cc.inherited_methods {
+            methods.extend(self.inherited_methods(context, cc));
+        }
+        self.resolve_collisions(&mut methods, &mut fields)?;
+        Ok((methods, fields, declared))
+    }
+
+    /// Returns the Rust name of a method declared by the class, as generated by [Self::write].
+    pub(crate) fn method_rust_name(
+        &self,
+        context: &Context,
+        name: &str,
+        descriptor: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let cc = context.config.resolve_class(self.java.path().as_str());
+        let (methods, _, declared) = self.resolved_members(context, &cc)?;
+        let method = methods[..declared]
+            .iter()
+            .find(|method| method.java.name() == name && method.java.descriptor().to_string() == descriptor);
+        Ok(method.and_then(|method| method.rust_name()).map(str::to_string))
+    }
+
+    /// Collects the methods and fields of the class included by `cc`, see [Self::apply_kotlin_metadata].
+    pub(crate) fn members(&self, context: &Context, cc: &ClassConfig) -> (Vec<Method<'_>>, Vec<Field<'_>>) {
+        let mut methods: Vec<Method> = Vec::new();
+        for method in self.java.methods() {
+            let mut method = Method::new(&self.java, method);
+            if cc.includes_method(&method.java) {
+                let member = cc.method(method.java.name(), &method.java.descriptor().to_string());
+                method.rename = member.rename.map(str::to_string);
+                method.naming = cc.naming;
+                method.generics = cc.generics;
+                methods.push(method);
+            }
+        }
+        let mut fields: Vec<Field> = Vec::new();
+        for field in self.java.fields() {
+            let mut field = Field::new(&self.java, field);
+            if cc.includes_field(&field.java) {
+                let member = cc.field(field.java.name(), &field.java.descriptor().to_string());
+                field.rename = member.rename.map(str::to_string);
+                field.set_naming(cc.naming);
+                field.generics = cc.generics;
+                fields.push(field);
+            }
+        }
+        if let Some(kotlin) = &self.kotlin {
+            self.apply_kotlin_metadata(context, kotlin, &mut methods, &mut fields, cc);
+        }
+        (methods, fields)
+    }
+
+    /// Hides synthetic members and `internal` declarations, which are public in bytecode, and applies the
+    /// nullability and property names of Kotlin declarations. With `Rule::kotlin_objects`, members of `object`
+    /// declarations are generated as static-like functions reading the instance.
+    fn apply_kotlin_metadata(
+        &self,
+        context: &Context,
+        kotlin: &KotlinMetadata,
+        methods: &mut Vec<Method>,
+        fields: &mut Vec<Field>,
+        cc: &ClassConfig,
+    ) {
+        let path = self.java.path().as_str();
+        let descriptor = format!("L{path};");
+        let singleton_field = match kotlin.kind {
+            KotlinClassKind::Object => Some((path, "INSTANCE")),
+            KotlinClassKind::CompanionObject => path.rsplit_once('$'),
+            _ => None,
+        };
+        let singleton = singleton_field
+            .filter(|_| cc.kotlin_objects)
+            .filter(|(class, field)| {
+                context.java_class(class).is_some_and(|class| {
+                    class
+                        .fields()
+                        .any(|f| f.name == *field && f.descriptor.to_string() == descriptor)
+                })
+            })
+            .map(|(class, field)| KotlinSingleton {
+                class: class.to_string(),
+                field: field.to_string(),
+                descriptor: descriptor.clone(),
+            });
+
+        methods.retain_mut(|method| {
+            if method.java.is_synthetic() {
+                return false;
+            }
+            let descriptor = method.java.descriptor();
+            method.kotlin = kotlin
+                .method(method.java.name(), &descriptor.to_string(), descriptor.parameters.len())
+                .cloned();
+            method.singleton = singleton.clone();
+            method.kotlin.as_ref().is_none_or(|k| k.visibility.is_api()) || cc.include_private_methods
+        });
+
+        fields.retain_mut(|field| {
+            if field.java.is_synthetic() {
+                return false;
+            }
+            let name = field.java.name();
+            let singleton = match kotlin.kind {
+                KotlinClassKind::Object => name == "INSTANCE",
+                _ => kotlin.companion_object.as_deref() == Some(name),
+            };
+            let declared = kotlin.field(name, &field.java.descriptor().to_string());
+            field.kotlin_nullability = match declared {
+                _ if singleton && field.java.is_static() => Nullability::NonNull,
+                Some(declared) => declared.nullability,
+                None => Nullability::Unknown,
+            };
+            declared.is_none_or(|k| k.visibility.is_api()) || cc.include_private_fields
+        });
+    }
+
+    /// Fills the name_counts map with all field and method names, and the names of methods added by the preamble
+    /// and for enums
     fn fill_name_counts(&self, methods: &[Method], fields: &[Field]) -> std::collections::HashMap<String, usize> {
         use std::collections::HashMap;
 
         let mut name_counts = HashMap::new();
+        for name in reserved_names(self.java.path().as_str()) {
+            name_counts.insert(name.to_string(), 1);
+        }
+        if self.java.is_enum() {
+            for name in ENUM_METHODS {
+                name_counts.insert(name.to_string(), 1);
+            }
+        }
 
         // Fill name_counts with all names from fields
         for field in fields {
@@ -210,35 +442,39 @@ impl Class {
 
     /// Resolves method name collisions using a hardcoded fallback strategy:
     /// Java -> JavaShortSignature -> JavaLongSignature
-    /// Only colliding methods are upgraded to the next mangling level.
-    fn resolve_collisions(&self, methods: &mut [Method], fields: &[Field]) -> anyhow::Result<()> {
-        use crate::identifiers::MethodManglingStyle;
-
+    /// Only colliding methods are upgraded to the next mangling level. Members whose names still collide after
+    /// being converted to snake_case (see [NamingConvention::SnakeCase]) keep their Java names.
+    fn resolve_collisions(&self, methods: &mut [Method], fields: &mut [Field]) -> anyhow::Result<()> {
         // Start with all methods using Java style
         for method in methods.iter_mut() {
             method.set_mangling_style(MethodManglingStyle::Java);
         }
+        self.upgrade_colliding(methods, fields);
 
-        for style in [
-            MethodManglingStyle::JavaShortSignature,
-            MethodManglingStyle::JavaLongSignature,
-        ] {
-            let name_counts = self.fill_name_counts(methods, fields);
-
-            let has_collisions = name_counts.values().any(|&count| count >= 2);
-            if !has_collisions {
-                return Ok(()); // All names are unique, we're done
+        let name_counts = self.fill_name_counts(methods, fields);
+        let collides = |name: &str| name_counts.get(name).unwrap_or(&0) >= &2;
+        let mut renamed = false;
+        for method in methods.iter_mut() {
+            if method.naming != NamingConvention::Java && method.rust_name().is_some_and(collides) {
+                method.naming = NamingConvention::Java;
+                method.set_mangling_style(MethodManglingStyle::Java);
+                renamed = true;
             }
-
-            // Upgrade methods that have collisions to the next mangling style
-            for method in methods.iter_mut() {
-                if let Some(name) = method.rust_name()
-                    && name_counts.get(name).unwrap_or(&0) >= &2
-                {
-                    method.set_mangling_style(style);
-                }
+        }
+        for field in fields.iter_mut() {
+            let colliding = match &field.rust_names {
+                Ok(FieldMangling::ConstValue(name, _)) => collides(name),
+                Ok(FieldMangling::GetSet(get, set)) => collides(get) || collides(set),
+                Err(_) => false,
+            };
+            if colliding && field.naming != NamingConvention::Java && field.rename.is_none() {
+                field.set_naming(NamingConvention::Java);
+                renamed = true;
             }
         }
+        if renamed {
+            self.upgrade_colliding(methods, fields);
+        }
 
         let name_counts = self.fill_name_counts(methods, fields);
         let has_collisions = name_counts.values().any(|&count| count >= 2);
@@ -259,4 +495,65 @@ impl Class {
             conflicting_names.join(", ")
         ))
     }
+
+    /// Upgrades colliding methods to the next mangling style, until there are no collisions or no styles left.
+    fn upgrade_colliding(&self, methods: &mut [Method], fields: &[Field]) {
+        for style in [
+            MethodManglingStyle::JavaShortSignature,
+            MethodManglingStyle::JavaLongSignature,
+        ] {
+            let name_counts = self.fill_name_counts(methods, fields);
+
+            let has_collisions = name_counts.values().any(|&count| count >= 2);
+            if !has_collisions {
+                return; // All names are unique, we're done
+            }
+
+            // Upgrade methods that have collisions to the next mangling style
+            for method in methods.iter_mut() {
+                if let Some(name) = method.rust_name()
+                    && name_counts.get(name).unwrap_or(&0) >= &2
+                {
+                    method.set_mangling_style(style);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the direct superinterfaces and superclass of `class`, with the type arguments of `current` (the same
+/// class) substituted into them. Raw types and classes without a signature give raw superclasses.
+pub(crate) fn super_classes(class: &JavaClass, current: &ClassTypeSignature) -> Vec<ClassTypeSignature> {
+    if let Some(sig) = class.signature()
+        && let Some(vars) = type_args(class, current)
+    {
+        return sig
+            .interfaces
+            .iter()
+            .chain([&sig.superclass])
+            .map(|super_class| super_class.substitute(&vars))
+            .collect();
+    }
+    class
+        .interfaces()
+        .map(|i| Id(i))
+        .chain(class.super_path())
+        .map(|path| ClassTypeSignature {
+            path: path.as_str().to_string(),
+            args: Vec::new(),
+        })
+        .collect()
+}
+
+/// Returns the type arguments of `current` by the names of the type parameters of `class` (the same class), or
+/// `None` for raw types of generic classes and types with wildcard arguments.
+pub(crate) fn type_args(class: &JavaClass, current: &ClassTypeSignature) -> Option<HashMap<String, TypeSignature>> {
+    let params = class.signature().map(|sig| sig.params).unwrap_or_default();
+    let args: Vec<TypeSignature> = (current.args.iter())
+        .map(|arg| match arg {
+            TypeArgument::Exact(ty) => Some(ty.clone()),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    (args.len() == params.len()).then(|| params.into_iter().zip(args).collect())
 }
@@ -0,0 +1,201 @@
+use std::collections::{HashMap, VecDeque};
+
+use cafebabe::descriptors::{FieldType, ReturnDescriptor};
+
+use super::Context;
+use crate::config::ClassConfig;
+use crate::parser_util::{Id, JavaClass, JavaField, JavaMethod};
+
+impl Context<'_> {
+    /// Includes classes referenced by included classes, see `Rule::include_dependencies`.
+    pub fn include_dependencies(&mut self) -> anyhow::Result<()> {
+        // included class => remaining depth of its dependencies to include
+        let mut remaining: HashMap<String, u32> = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        let mut roots: Vec<String> = self.all_classes.keys().cloned().collect();
+        roots.sort(); // keeps the report stable
+        for path in roots {
+            let depth = self.config.resolve_class(&path).include_dependencies;
+            if depth > 0 {
+                remaining.insert(path.clone(), depth);
+                queue.push_back(path);
+            }
+        }
+
+        while let Some(path) = queue.pop_front() {
+            let depth = remaining[&path] - 1;
+            let class = self.all_classes[&path].clone();
+            let cc = self.config.resolve_class(&path);
+
+            for (dep, reason) in dependencies_of(&class.java, &cc) {
+                if self.all_classes.contains_key(&dep) {
+                    // Already included, walk it again only if it's reached with more remaining depth.
+                    if remaining.get(&dep).copied().unwrap_or(0) >= depth {
+                        continue;
+                    }
+                } else {
+                    let Some(java) = self
+                        .excluded_classes
+                        .remove(&dep)
+                        .or_else(|| self.reference_classes.remove(&dep))
+                    else {
+                        continue;
+                    };
+                    self.progress
+                        .lock()
+                        .unwrap()
+                        .force_update(format!("included dependency {dep}: {reason}").as_str());
+                    self.report.lock().unwrap().include(&dep, reason);
+                    self.insert_class(java)?;
+                }
+
+                if depth > 0 {
+                    remaining.insert(dep.clone(), depth);
+                    queue.push_back(dep);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns classes referenced by the class and its members to be generated, with the reason of each reference.
+pub(crate) fn dependencies_of(class: &JavaClass, cc: &ClassConfig) -> Vec<(String, String)> {
+    let this = class.path().as_str();
+    let mut deps = Vec::new();
+
+    if let Some(super_path) = class.super_path() {
+        deps.push((super_path.as_str().to_string(), format!("superclass of {this}")));
+    }
+    for interface in class.interfaces() {
+        deps.push((Id::from(interface).as_str().to_string(), format!("interface of {this}")));
+    }
+
+    for field in class.fields().map(JavaField::from) {
+        if !cc.includes_field(&field) {
+            continue;
+        }
+        if let FieldType::Object(class_name) = &field.descriptor().field_type {
+            deps.push((
+                Id::from(class_name).as_str().to_string(),
+                format!("type of field {this}.{}", field.name()),
+            ));
+        }
+    }
+
+    for method in class.methods().map(JavaMethod::from) {
+        if !cc.includes_method(&method) {
+            continue;
+        }
+        let descriptor = method.descriptor();
+        let return_type = match &descriptor.return_type {
+            ReturnDescriptor::Return(desc) => Some(desc),
+            ReturnDescriptor::Void => None,
+        };
+        for desc in descriptor.parameters.iter().chain(return_type) {
+            if let FieldType::Object(class_name) = &desc.field_type {
+                deps.push((
+                    Id::from(class_name).as_str().to_string(),
+                    format!("signature of method {this}.{}", method.name()),
+                ));
+            }
+        }
+    }
+
+    deps
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{ACC_PUBLIC, ClassFile, Member, config, context};
+
+    fn sorted_keys<'a, V>(map: impl IntoIterator<Item = (&'a String, V)>) -> Vec<&'a str> {
+        let mut keys: Vec<_> = map.into_iter().map(|(key, _)| key.as_str()).collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn include_dependencies() {
+        let config = config(
+            r#"
+rules:
+  - match: "com/example/A"
+    include: true
+    include_dependencies: 2
+  - match: "com/example/B"
+    reference_only: true
+"#,
+        );
+        let context = context(
+            &config,
+            &[
+                ClassFile::new("java/lang/Object"),
+                ClassFile::new("com/example/A").field(Member::new(ACC_PUBLIC, "b", "Lcom/example/B;")),
+                ClassFile::new("com/example/B").extends("com/example/C"),
+                ClassFile::new("com/example/C").method(Member::new(ACC_PUBLIC, "d", "()Lcom/example/D;")),
+                ClassFile::new("com/example/D"),
+                ClassFile::new("com/example/Unrelated"),
+            ],
+        );
+
+        // Classes up to two references away from `A` are included: `B` and `Object` (the superclass of `A`) are one
+        // away, `C` (the superclass of `B`) two. `D` (returned by `C`) is three away.
+        assert_eq!(
+            sorted_keys(&context.all_classes),
+            ["com/example/A", "com/example/B", "com/example/C", "java/lang/Object"]
+        );
+        assert_eq!(
+            sorted_keys(&context.excluded_classes),
+            ["com/example/D", "com/example/Unrelated"]
+        );
+        assert!(context.reference_classes.is_empty());
+
+        let report = context.report.lock().unwrap().to_text();
+        assert!(report.contains("included dependency com/example/B: type of field com/example/A.b\n"));
+        assert!(report.contains("included dependency com/example/C: superclass of com/example/B\n"));
+    }
+
+    #[test]
+    fn include_dependencies_member_rules() {
+        let config = config(
+            r#"
+rules:
+  - match: "com/example/A"
+    include: true
+    include_dependencies: 1
+    methods:
+      - name: excluded
+        include: false
+    fields:
+      - name: hidden
+        include: true
+"#,
+        );
+        let context = context(
+            &config,
+            &[
+                ClassFile::new("java/lang/Object"),
+                ClassFile::new("com/example/A")
+                    .method(Member::new(ACC_PUBLIC, "excluded", "()Lcom/example/Excluded;"))
+                    .field(Member::new(0, "hidden", "Lcom/example/Hidden;"))
+                    .field(Member::new(0, "private", "Lcom/example/Private;")),
+                ClassFile::new("com/example/Excluded"),
+                ClassFile::new("com/example/Hidden"),
+                ClassFile::new("com/example/Private"),
+            ],
+        );
+
+        // Only the members generated with the rules of `A` are walked.
+        assert_eq!(
+            sorted_keys(&context.all_classes),
+            ["com/example/A", "com/example/Hidden", "java/lang/Object"]
+        );
+        assert_eq!(
+            sorted_keys(&context.excluded_classes),
+            ["com/example/Excluded", "com/example/Private"]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Rust enums generated for Java enums, so that constants returned from Java can be matched on:
+//!
+//! ```ignore
+//! match thread.getState()?.unwrap().to_rust() {
+//!     Some(Thread_StateEnum::RUNNABLE) => {}
+//!     _ => {}
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use super::classes::Class;
+use super::cstring;
+use crate::config::ClassConfig;
+use crate::emit::Context;
+use crate::identifiers::{NamingConvention, rust_ident};
+use crate::parser_util::JavaField;
+
+/// Methods added to the impl of Java enums, see [Class::write_enum].
+pub(crate) const ENUM_METHODS: &[&str] = &["to_rust", "from_rust"];
+
+/// The generated Rust enum of a Java enum.
+pub(crate) struct EnumConversions {
+    /// The Rust enum, next to the struct of the class.
+    pub enum_: TokenStream,
+    /// `to_rust` and `from_rust` for the impl of the class.
+    pub methods: TokenStream,
+}
+
+impl Class {
+    /// Generates a Rust enum with a variant for each constant of a Java enum. Constants are matched by their
+    /// `ordinal()`, read from the static fields once, so they may be declared in a different order at runtime.
+    /// Returns `None` for other classes, enums without constants, or if the Rust enum can't be named.
+    pub(crate) fn write_enum(&self, context: &Context, cc: &ClassConfig) -> Option<EnumConversions> {
+        if !self.java.is_enum() {
+            return None;
+        }
+        let constants: Vec<JavaField> = self
+            .java
+            .fields()
+            .map(JavaField::from)
+            .filter(|field| field.is_enum() && field.is_static())
+            .collect();
+        if constants.is_empty() {
+            return None;
+        }
+
+        let enum_name = format!("{}Enum", self.rust.struct_name);
+        let taken = context
+            .all_classes
+            .values()
+            .any(|class| class.rust.mod_ == self.rust.mod_ && class.rust.struct_name == enum_name);
+        if taken {
+            return None;
+        }
+
+        // Variants that would collide after renaming keep their Java names.
+        let mut variants = Vec::new();
+        for naming in [cc.naming, NamingConvention::Java] {
+            let Ok(named) = constants
+                .iter()
+                .map(|field| rust_ident(&naming.variant(field.name())))
+                .collect::<Result<Vec<_>, _>>()
+            else {
+                return None;
+            };
+            variants = named;
+            if variants.iter().collect::<HashSet<_>>().len() == variants.len() {
+                break;
+            }
+        }
+
+        let visibility = match self.java.is_public() || cc.include_private_classes {
+            true => quote!(pub),
+            false => quote!(),
+        };
+        let struct_name = &self.rust.struct_name;
+        let enum_docs = format!(
+            "The constants of [`{struct_name}`], see [`{struct_name}::to_rust`] and [`{struct_name}::from_rust`]."
+        );
+        let variant_docs = constants
+            .iter()
+            .zip(&variants)
+            .map(|(field, variant)| match field.name() == variant {
+                true => quote!(),
+                false => {
+                    let docs = format!("`{}`", field.name());
+                    quote!(#[doc = #docs])
+                }
+            });
+        let enum_ident = format_ident!("{enum_name}");
+        let variants: Vec<_> = variants.iter().map(|variant| format_ident!("{variant}")).collect();
+        let enum_ = quote!(
+            #[doc = #enum_docs]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #visibility enum #enum_ident {
+                #(#variant_docs #variants,)*
+            }
+        );
+
+        let count = Literal::usize_unsuffixed(constants.len());
+        let java_names = constants.iter().map(|field| cstring(field.name()));
+        let descriptor = cstring(&constants[0].descriptor().to_string());
+        let to_rust_docs = format!(
+            "Returns the constant of [`{enum_name}`], or `None` for constants added after generating the bindings."
+        );
+        let methods = quote!(
+            #[doc = #to_rust_docs]
+            pub fn to_rust<'env>(self: &::java_spaghetti::Ref<'env, Self>) -> ::std::option::Option<#enum_ident> {
+                static __ORDINALS: ::std::sync::OnceLock<[i32; #count]> = ::std::sync::OnceLock::new();
+                const __VARIANTS: [#enum_ident; #count] = [#(#enum_ident::#variants),*];
+                let __jni_env = self.env();
+                unsafe {
+                    let __jni_ordinals = __ORDINALS.get_or_init(|| {
+                        __VARIANTS.map(|variant| __jni_env.get_enum_ordinal(&Self::from_rust(__jni_env, variant)))
+                    });
+                    let __jni_ordinal = __jni_env.get_enum_ordinal(self);
+                    __jni_ordinals.iter().position(|&ordinal| ordinal == __jni_ordinal).map(|index| __VARIANTS[index])
+                }
+            }
+
+            /// Returns the Java constant of `value`.
+            pub fn from_rust<'env>(__jni_env: ::java_spaghetti::Env<'env>, value: #enum_ident) -> ::java_spaghetti::Local<'env, Self> {
+                use ::java_spaghetti::ReferenceType;
+                static __FIELDS: [::std::sync::OnceLock<::java_spaghetti::JFieldID>; #count] =
+                    [const { ::std::sync::OnceLock::new() }; #count];
+                const __NAMES: [&::std::ffi::CStr; #count] = [#(#java_names),*];
+                let __jni_class = Self::jni_get_class(__jni_env).unwrap();
+                unsafe {
+                    let __jni_field = *__FIELDS[value as usize].get_or_init(|| {
+                        __jni_env.require_static_field(__jni_class, __NAMES[value as usize], #descriptor)
+                    });
+                    __jni_env
+                        .get_static_object_field(__jni_class, __jni_field)
+                        .expect("enum constant is null")
+                }
+            }
+        );
+
+        Some(EnumConversions { enum_, methods })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::*;
+
+    #[test]
+    fn constant_with_body() {
+        let constant = ACC_PUBLIC | ACC_STATIC | ACC_FINAL | ACC_ENUM;
+        let mut classes = jdk_classes();
+        classes.push(ClassFile::new("java/lang/Enum").access(ACC_PUBLIC | ACC_ABSTRACT));
+        // `PLUS { ... }` is compiled to `Op$1`, an enum class without constants extending `Op`.
+        classes.push(
+            ClassFile::new("com/example/Op")
+                .access(ACC_PUBLIC | ACC_ABSTRACT | ACC_ENUM)
+                .extends("java/lang/Enum")
+                .field(Member::new(constant, "PLUS", "Lcom/example/Op;"))
+                .field(Member::new(constant, "MINUS", "Lcom/example/Op;"))
+                .field(Member::new(
+                    ACC_STATIC | ACC_FINAL | ACC_SYNTHETIC,
+                    "$VALUES",
+                    "[Lcom/example/Op;",
+                ))
+                .method(Member::new(ACC_PUBLIC | ACC_ABSTRACT, "apply", "(II)I")),
+        );
+        classes.push(
+            ClassFile::new("com/example/Op$1")
+                .access(ACC_FINAL | ACC_ENUM)
+                .extends("com/example/Op")
+                .method(Member::new(ACC_PUBLIC, "apply", "(II)I")),
+        );
+        let code = generate("", &classes);
+
+        let compact = compact(&code);
+        assert!(compact.contains("pubenumOpEnum{PLUS,MINUS,}"), "{code}");
+        assert!(compact.contains("const__NAMES:[&::std::ffi::CStr;2]=[c\"PLUS\",c\"MINUS\"];"));
+        assert!(compact.contains("c\"Lcom/example/Op;\""));
+        assert_eq!(compact.matches("pubfnto_rust<'env>").count(), 1);
+        assert_eq!(compact.matches("pubfnfrom_rust<'env>").count(), 1);
+
+        assert!(
+            compact
+                .contains("pubfnto_rust<'env>(self:&::java_spaghetti::Ref<'env,Self>)->::std::option::Option<OpEnum>")
+        );
+        assert!(compact.contains(
+            "pubfnfrom_rust<'env>(__jni_env:::java_spaghetti::Env<'env>,value:OpEnum)->::java_spaghetti::Local<'env,Self>"
+        ));
+        // `to_rust` compares ordinals, so it works on the instances of `Op$1` too, which don't get an enum.
+        assert!(!compact.contains("Op__1Enum"));
+    }
+}
@@ -1,20 +1,30 @@
 use anyhow::anyhow;
 use cafebabe::constant_pool::LiteralConstant;
 use cafebabe::descriptors::{FieldDescriptor, FieldType};
-use proc_macro2::{Literal, TokenStream};
+use proc_macro2::{Ident, Literal, TokenStream};
 use quote::{format_ident, quote};
 
 use super::cstring;
+use super::generics::{TypeEmitter, TypeScope, arg_check_class, emit_type_params, needs_return_check};
+use super::java_proxy::java_type_name;
 use super::known_docs_url::KnownDocsUrl;
 use crate::config::ClassConfig;
 use crate::emit::Context;
-use crate::identifiers::{FieldMangling, mangle_field};
-use crate::parser_util::{Id, JavaClass, JavaField};
+use crate::identifiers::{FieldMangling, NamingConvention, mangle_field};
+use crate::parser_util::{Id, JavaClass, JavaField, Nullability, TypeSignature};
+use crate::util::Rejection;
 
 pub struct Field<'a> {
     pub class: &'a JavaClass,
     pub java: JavaField<'a>,
     pub rust_names: Result<FieldMangling<'a>, anyhow::Error>,
+    /// Set by `MemberRule::rename`, which takes precedence over the naming convention.
+    pub rename: Option<String>,
+    pub naming: NamingConvention,
+    /// Whether the generic signature is used, see `Rule::generics`.
+    pub generics: bool,
+    /// Nullability declared by Kotlin metadata, see [crate::parser_util::KotlinMetadata].
+    pub kotlin_nullability: Nullability,
 }
 
 impl<'a> Field<'a> {
@@ -22,29 +32,65 @@ impl<'a> Field<'a> {
         Self {
             class,
             java: JavaField::from(java),
-            rust_names: mangle_field(JavaField::from(java)),
+            rust_names: mangle_field(JavaField::from(java), None, NamingConvention::Java),
+            rename: None,
+            naming: NamingConvention::Java,
+            generics: false,
+            kotlin_nullability: Nullability::Unknown,
         }
     }
 
-    pub fn emit(&self, context: &Context, cc: &ClassConfig, mod_: &str) -> anyhow::Result<TokenStream> {
+    pub fn set_naming(&mut self, naming: NamingConvention) {
+        self.naming = naming;
+        self.rust_names = mangle_field(self.java, self.rename.as_deref(), naming);
+    }
+
+    pub fn emit(
+        &self,
+        context: &Context,
+        cc: &ClassConfig,
+        class_scope: &TypeScope,
+        mod_: &str,
+    ) -> anyhow::Result<TokenStream> {
         let mut emit_reject_reasons = Vec::new();
 
         let descriptor = &self.java.descriptor();
 
-        let rust_set_type = emit_type(
+        let signature = (self.java.signature())
+            .filter(|_| self.generics)
+            .unwrap_or_else(|| TypeSignature::from(*descriptor));
+        let scope = match self.java.is_static() {
+            false => class_scope.clone(),
+            true => TypeScope::default(),
+        };
+        let mut types = TypeEmitter::new(context, &scope, mod_);
+        let (rust_set_type, set_signature) = types.emit(
+            &signature,
             descriptor,
-            context,
-            mod_,
             RustTypeFlavor::ImplAsArg,
             &mut emit_reject_reasons,
-        )?;
-        let rust_get_type = emit_type(
+        );
+        let set_type_params = emit_type_params(&types.fresh);
+        let nullability = match emit_fragment_type(descriptor) {
+            "object" => self.kotlin_nullability.or(self.java.nullability()),
+            _ => Nullability::Unknown,
+        };
+        // Values of type variables would have to be checked at runtime, which getters have no way to fail with, so
+        // they return the erased type.
+        let get_signature = match needs_return_check(&signature) {
+            true => TypeSignature::from(*descriptor),
+            false => signature.clone(),
+        };
+        // Getters return an `Option` like those of other fields, even if the field is declared non-null, since Java
+        // doesn't enforce the annotations.
+        let (rust_get_type, _) = types.emit(
+            &get_signature,
             descriptor,
-            context,
-            mod_,
             RustTypeFlavor::OptionLocal,
             &mut emit_reject_reasons,
-        )?;
+        );
+
+        let erased_class = arg_check_class(&set_signature, descriptor, context);
 
         let static_fragment = match self.java.is_static() {
             false => "",
@@ -61,7 +107,17 @@ impl<'a> Field<'a> {
         }
 
         if !emit_reject_reasons.is_empty() {
-            // TODO log
+            let static_ = if self.java.is_static() { "static " } else { "" };
+            context.report.lock().unwrap().reject(
+                self.class.path().as_str(),
+                Rejection {
+                    kind: "field",
+                    name: self.java.name().to_string(),
+                    descriptor: descriptor.to_string(),
+                    signature: format!("{static_}{} {}", java_type_name(descriptor)?, self.java.name()),
+                    reasons: emit_reject_reasons,
+                },
+            );
             return Ok(TokenStream::new());
         }
 
@@ -96,6 +152,10 @@ impl<'a> Field<'a> {
             Some(url) => format!("{keywords} {url}"),
             None => format!("{keywords} {}", self.java.name()),
         };
+        let javadoc = context
+            .javadoc
+            .field(self.class.path().as_str(), self.java.name())
+            .map(|doc| doc.render(context, mod_));
 
         match self.rust_names.as_ref().map_err(|e| anyhow!("bad mangling: {e}"))? {
             FieldMangling::ConstValue(constant, value) => {
@@ -110,8 +170,10 @@ impl<'a> Field<'a> {
                     rust_get_type
                 };
 
+                let notes = emit_notes(&Vec::from_iter(javadoc));
                 out.extend(quote!(
                     #[doc = #docs]
+                    #notes
                     #attributes
                     pub const #constant: #ty = #value;
                 ));
@@ -138,8 +200,26 @@ impl<'a> Field<'a> {
 
                 let get_docs = format!("**get** {docs}");
                 let set_docs = format!("**set** {docs}");
+                let mut notes = Vec::from_iter(javadoc);
+                let mut value_check = TokenStream::new();
+                if let Some(class_name) = &erased_class {
+                    notes.push(format!("The value is a `{}`.", java_type_name(self.java.descriptor())?));
+                    value_check = emit_arg_check(&format_ident!("value"), class_name);
+                }
+                let mut get_notes = notes.clone();
+                if nullability == Nullability::NonNull {
+                    get_notes.push("The value is declared non-null.".to_string());
+                }
+                let get_notes = emit_notes(&get_notes);
+                match nullability {
+                    Nullability::NonNull => notes.push("The value must not be null.".to_string()),
+                    Nullability::Nullable => notes.push("The value may be null.".to_string()),
+                    Nullability::Unknown => {}
+                }
+                let set_notes = emit_notes(&notes);
                 out.extend(quote!(
                     #[doc = #get_docs]
+                    #get_notes
                     #attributes
                     pub fn #get<'env>(#env_param) -> #rust_get_type {
                         use ::java_spaghetti::ReferenceType;
@@ -161,18 +241,33 @@ impl<'a> Field<'a> {
                         quote!('env)
                     };
 
+                    // Object setters always return a `Result`, so that their signature doesn't change when the
+                    // type of the field is degraded and the value has to be checked.
+                    let (set_ret, set_ok) = match field_fragment == "object" {
+                        false => (quote!(), quote!()),
+                        true => {
+                            let throwable = context.throwable_rust_path(mod_);
+                            (
+                                quote!(-> ::std::result::Result<(), ::java_spaghetti::Local<'env, #throwable>>),
+                                quote!(Ok(())),
+                            )
+                        }
+                    };
                     out.extend(quote!(
                         #[doc = #set_docs]
+                        #set_notes
                         #attributes
-                        pub fn #set<#lifetimes>(#env_param, value: #rust_set_type) {
+                        pub fn #set<#lifetimes #set_type_params>(#env_param, value: #rust_set_type) #set_ret {
                             use ::java_spaghetti::ReferenceType;
                             static __FIELD: ::std::sync::OnceLock<::java_spaghetti::JFieldID> = ::std::sync::OnceLock::new();
                             #env_let
                             let __jni_class = Self::jni_get_class(__jni_env).unwrap();
                             unsafe {
+                                #value_check
                                 let __jni_field = *__FIELD.get_or_init(|| __jni_env.#require_field(__jni_class, #java_name, #descriptor));
                                 __jni_env.#set_field(#this_or_class, __jni_field, value);
                             }
+                            #set_ok
                         }
                     ));
                 }
@@ -235,15 +330,18 @@ pub fn emit_constant(constant: &LiteralConstant<'_>, descriptor: &FieldDescripto
 pub enum RustTypeFlavor {
     ImplAsArg,
     OptionLocal,
+    /// For values annotated as non-null.
+    Local,
     OptionRef,
     Arg,
     Return,
 }
 
-fn flavorify(ty: TokenStream, flavor: RustTypeFlavor) -> TokenStream {
+pub fn flavorify(ty: TokenStream, flavor: RustTypeFlavor) -> TokenStream {
     match flavor {
         RustTypeFlavor::ImplAsArg => quote!(impl ::java_spaghetti::AsArg<#ty>),
         RustTypeFlavor::OptionLocal => quote!(::std::option::Option<::java_spaghetti::Local<'env, #ty>>),
+        RustTypeFlavor::Local => quote!(::java_spaghetti::Local<'env, #ty>),
         RustTypeFlavor::OptionRef => quote!(::std::option::Option<::java_spaghetti::Ref<'env, #ty>>),
         RustTypeFlavor::Arg => quote!(::java_spaghetti::Arg<#ty>),
         RustTypeFlavor::Return => quote!(::java_spaghetti::Return<'env, #ty>),
@@ -269,10 +367,7 @@ pub fn emit_type(
             FieldType::Float => quote!(f32),
             FieldType::Double => quote!(f64),
             FieldType::Object(class_name) => {
-                let class = Id::from(class_name);
-                if !context.all_classes.contains_key(class.as_str()) {
-                    reject_reasons.push("ERROR:  missing class for field/argument type");
-                }
+                let class = emitted_class(Id::from(class_name), context, &flavor, reject_reasons);
                 if let Ok(path) = context.java_to_rust_path(class, mod_) {
                     flavorify(path, flavor)
                 } else {
@@ -295,11 +390,7 @@ pub fn emit_type(
             FieldType::Float => quote!(::java_spaghetti::FloatArray),
             FieldType::Double => quote!(::java_spaghetti::DoubleArray),
             FieldType::Object(class_name) => {
-                let class = Id::from(class_name);
-
-                if !context.all_classes.contains_key(class.as_str()) {
-                    reject_reasons.push("ERROR:  missing class for field type");
-                }
+                let class = emitted_class(Id::from(class_name), context, &flavor, reject_reasons);
 
                 let path = match context.java_to_rust_path(class, mod_) {
                     Ok(path) => path,
@@ -321,6 +412,63 @@ pub fn emit_type(
     Ok(res)
 }
 
+/// Returns the generated class to refer to for `class`, see [Context::emitted_class_for].
+pub fn emitted_class<'c>(
+    class: Id<'c>,
+    context: &'c Context<'_>,
+    flavor: &RustTypeFlavor,
+    reject_reasons: &mut Vec<&'static str>,
+) -> Id<'c> {
+    match context.emitted_class_for(class) {
+        Some(emitted) => {
+            if emitted != class && matches!(flavor, RustTypeFlavor::Return) {
+                // Rust code could return any instance of the replacement class to Java.
+                reject_reasons.push("ERROR:  reference-only class cannot be returned to Java");
+            }
+            emitted
+        }
+        None => {
+            reject_reasons.push("ERROR:  missing class for field/argument type");
+            class
+        }
+    }
+}
+
+/// Returns the JNI class name of an object or array type that is replaced by another class in generated
+/// signatures, so that arguments of this type have to be checked at runtime.
+pub fn erased_class_name(descriptor: &FieldDescriptor, context: &Context<'_>) -> Option<String> {
+    let FieldType::Object(class_name) = &descriptor.field_type else {
+        return None;
+    };
+    let class = Id::from(class_name);
+    if context.emitted_class_for(class)? == class {
+        return None;
+    }
+    Some(match descriptor.dimensions {
+        0 => class.as_str().to_string(),
+        _ => descriptor.to_string(),
+    })
+}
+
+/// Generates extra doc comment paragraphs, e.g. about types replaced in the signature.
+pub fn emit_notes(notes: &[String]) -> TokenStream {
+    let mut out = TokenStream::new();
+    for doc in notes {
+        out.extend(quote!(#[doc = ""] #[doc = #doc]));
+    }
+    out
+}
+
+/// Generates a runtime check that the argument is an instance of the JNI class, see [erased_class_name]. The
+/// exception is returned with `?`.
+pub fn emit_arg_check(arg_name: &Ident, class_name: &str) -> TokenStream {
+    let class_name = cstring(class_name);
+    quote!({
+        static __CLASS: ::std::sync::OnceLock<::java_spaghetti::JClass> = ::std::sync::OnceLock::new();
+        __jni_env.require_arg_instance_of(&#arg_name, #class_name, &__CLASS)?;
+    })
+}
+
 /// Contents of {get,set}_[static_]..._field, call_..._method_a.
 pub fn emit_fragment_type(descriptor: &FieldDescriptor) -> &'static str {
     if descriptor.dimensions == 0 {
@@ -339,3 +487,44 @@ pub fn emit_fragment_type(descriptor: &FieldDescriptor) -> &'static str {
         "object"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{ACC_PUBLIC, ClassFile, Member, compact, generate};
+
+    #[test]
+    fn degrade_missing_types() {
+        let out = generate(
+            r#"
+degrade_missing_types: true
+rules:
+  - match: ["java/lang/Object", "com/example/Base", "com/example/Holder"]
+    include: true
+"#,
+            &[
+                ClassFile::new("java/lang/Object"),
+                ClassFile::new("com/example/Base"),
+                ClassFile::new("com/example/Hidden").extends("com/example/Base"),
+                ClassFile::new("com/example/Holder")
+                    .field(Member::new(ACC_PUBLIC, "value", "Lcom/example/Hidden;"))
+                    .field(Member::new(ACC_PUBLIC, "base", "Lcom/example/Base;"))
+                    .field(Member::new(ACC_PUBLIC, "count", "I"))
+                    .method(Member::new(ACC_PUBLIC, "take", "(Lcom/example/Missing;)V")),
+            ],
+        );
+        let out = compact(&out);
+
+        // Unknown classes are replaced with `Object`, parsed ones with their nearest generated superclass.
+        assert!(out.contains("pubfntake<'env>(self:&::java_spaghetti::Ref<'env,Self>,arg0:impl::java_spaghetti::AsArg<super::super::java::lang::Object>,)"));
+        assert!(out.contains(r#"require_arg_instance_of(&arg0,c"com/example/Missing",&__CLASS)?;"#));
+        assert!(out.contains("pubfnvalue<'env>(self:&::java_spaghetti::Ref<'env,Self>)->::std::option::Option<::java_spaghetti::Local<'env,Base>>"));
+
+        // Setters of replaced types return the exception of the check.
+        assert!(out.contains("value:impl::java_spaghetti::AsArg<Base>)->::std::result::Result<(),::java_spaghetti::Local<'env,super::super::java::lang::Throwable>>"));
+        assert!(out.contains(r#"require_arg_instance_of(&value,c"com/example/Hidden",&__CLASS)?;"#));
+
+        // Other object setters return a `Result` too, primitive ones don't.
+        assert!(out.contains("pubfnset_base<'env,'obj>(self:&::java_spaghetti::Ref<'env,Self>,value:impl::java_spaghetti::AsArg<Base>)->::std::result::Result<(),::java_spaghetti::Local<'env,super::super::java::lang::Throwable>>"));
+        assert!(out.contains("pubfnset_count<'env>(self:&::java_spaghetti::Ref<'env,Self>,value:i32){"));
+    }
+}
@@ -0,0 +1,317 @@
+//! Type parameters of generic Java classes and methods, read from `Signature` attributes if `Rule::generics` is
+//! enabled.
+//!
+//! A generic class like `java.util.List<E>` is generated as `struct List<E = Object>`, so that `List` alone still
+//! names the erased type. Java only checks values against the erased types, so objects returned as a type variable
+//! are checked at runtime (failing with a `ClassCastException`), and so are arguments declared as a type variable
+//! with a bound other than `Object`. Field getters return the erased type instead.
+
+use cafebabe::descriptors::{FieldDescriptor, FieldType};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use super::Context;
+use super::fields::{RustTypeFlavor, emitted_class, erased_class_name, flavorify};
+use crate::identifiers::rust_ident;
+use crate::parser_util::{ClassTypeSignature, Id, TypeArgument, TypeSignature};
+
+const OBJECT: &str = "java/lang/Object";
+
+impl Context<'_> {
+    /// Returns the Java names of the type parameters of a generated class, empty if it is generated without them.
+    ///
+    /// Type parameters default to `java.lang.Object`, so classes are only generic if it is generated too.
+    pub(crate) fn class_type_params(&self, java_class: &str) -> &[String] {
+        match self.all_classes.get(java_class) {
+            Some(class) if self.all_classes.contains_key(OBJECT) => &class.type_params,
+            _ => &[],
+        }
+    }
+
+    fn has_struct(&self, mod_: &str, name: &str) -> bool {
+        let mut module = &self.module;
+        for fragment in mod_.split("::") {
+            match module.modules.get(fragment) {
+                Some(m) => module = m,
+                None => return false,
+            }
+        }
+        module.classes.contains_key(name)
+    }
+}
+
+/// Java type variables in scope, with the names of the Rust type parameters standing for them.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TypeScope {
+    vars: Vec<(String, String)>,
+}
+
+impl TypeScope {
+    /// Adds type parameters shadowing variables of the same name, returning their Rust names. Names are suffixed
+    /// with `_` where they would clash with other type parameters in scope or with structs of the module `mod_`.
+    pub(crate) fn add(&mut self, params: &[String], context: &Context, mod_: &str) -> anyhow::Result<Vec<Ident>> {
+        let mut idents = Vec::new();
+        for param in params {
+            let mut name = rust_ident(param)?;
+            while self.vars.iter().any(|(_, rust)| *rust == name) || context.has_struct(mod_, &name) {
+                name.push('_');
+            }
+            idents.push(format_ident!("{name}"));
+            self.vars.push((param.clone(), name));
+        }
+        Ok(idents)
+    }
+
+    fn get(&self, java: &str) -> Option<Ident> {
+        self.vars
+            .iter()
+            .rev()
+            .find(|(name, _)| name == java)
+            .map(|(_, rust)| format_ident!("{rust}"))
+    }
+}
+
+/// Generates Rust types for Java type signatures.
+pub(crate) struct TypeEmitter<'a, 'ctx> {
+    context: &'a Context<'ctx>,
+    scope: &'a TypeScope,
+    mod_: &'a str,
+    /// Type parameters introduced for wildcards and raw types in argument position, which accept any type argument.
+    pub fresh: Vec<Ident>,
+}
+
+impl<'a, 'ctx> TypeEmitter<'a, 'ctx> {
+    pub(crate) fn new(context: &'a Context<'ctx>, scope: &'a TypeScope, mod_: &'a str) -> Self {
+        Self {
+            context,
+            scope,
+            mod_,
+            fresh: Vec::new(),
+        }
+    }
+
+    /// Generates the Rust type like `fields::emit_type`, falling back to the erased `descriptor` where the signature
+    /// refers to a type variable out of scope, e.g. one of an enclosing class. Returns the signature used.
+    pub(crate) fn emit(
+        &mut self,
+        ty: &TypeSignature,
+        descriptor: &FieldDescriptor,
+        flavor: RustTypeFlavor,
+        reject_reasons: &mut Vec<&'static str>,
+    ) -> (TokenStream, TypeSignature) {
+        let (emitted, ty) = self.emit_unflavored(ty, descriptor, &flavor, reject_reasons);
+        match ty {
+            TypeSignature::Primitive(_) => (emitted, ty),
+            _ => (flavorify(emitted, flavor), ty),
+        }
+    }
+
+    /// Generates the Rust type of an argument like [Self::emit] with `RustTypeFlavor::ImplAsArg`, without the
+    /// `impl AsArg<...>` around reference types.
+    pub(crate) fn emit_arg_type(
+        &mut self,
+        ty: &TypeSignature,
+        descriptor: &FieldDescriptor,
+        reject_reasons: &mut Vec<&'static str>,
+    ) -> (TokenStream, TypeSignature) {
+        self.emit_unflavored(ty, descriptor, &RustTypeFlavor::ImplAsArg, reject_reasons)
+    }
+
+    fn emit_unflavored(
+        &mut self,
+        ty: &TypeSignature,
+        descriptor: &FieldDescriptor,
+        flavor: &RustTypeFlavor,
+        reject_reasons: &mut Vec<&'static str>,
+    ) -> (TokenStream, TypeSignature) {
+        let arg = matches!(flavor, RustTypeFlavor::ImplAsArg | RustTypeFlavor::Arg);
+        match self.emit_bare(ty, arg, flavor, reject_reasons) {
+            Some(emitted) => (emitted, ty.clone()),
+            None => {
+                let ty = TypeSignature::from(descriptor);
+                (self.emit_bare(&ty, arg, flavor, reject_reasons).unwrap(), ty)
+            }
+        }
+    }
+
+    /// Generates a class type in argument position, as used for `AssignableTo` impls.
+    pub(crate) fn emit_class(&mut self, class: &ClassTypeSignature) -> TokenStream {
+        self.emit_class_bare(class, true, &RustTypeFlavor::ImplAsArg, &mut Vec::new())
+    }
+
+    fn emit_bare(
+        &mut self,
+        ty: &TypeSignature,
+        arg: bool,
+        flavor: &RustTypeFlavor,
+        reject_reasons: &mut Vec<&'static str>,
+    ) -> Option<TokenStream> {
+        Some(match ty {
+            TypeSignature::Primitive(c) => match c {
+                'Z' => quote!(bool),
+                'B' => quote!(i8),
+                'C' => quote!(u16),
+                'S' => quote!(i16),
+                'I' => quote!(i32),
+                'J' => quote!(i64),
+                'F' => quote!(f32),
+                _ => quote!(f64),
+            },
+            TypeSignature::TypeVariable(name) => {
+                let ident = self.scope.get(name)?;
+                quote!(#ident)
+            }
+            TypeSignature::Array(element) => match **element {
+                TypeSignature::Primitive(c) => match c {
+                    'Z' => quote!(::java_spaghetti::BooleanArray),
+                    'B' => quote!(::java_spaghetti::ByteArray),
+                    'C' => quote!(::java_spaghetti::CharArray),
+                    'S' => quote!(::java_spaghetti::ShortArray),
+                    'I' => quote!(::java_spaghetti::IntArray),
+                    'J' => quote!(::java_spaghetti::LongArray),
+                    'F' => quote!(::java_spaghetti::FloatArray),
+                    _ => quote!(::java_spaghetti::DoubleArray),
+                },
+                ref element => {
+                    let element = self.emit_bare(element, arg, flavor, reject_reasons)?;
+                    let throwable = self.context.throwable_rust_path(self.mod_);
+                    quote!(::java_spaghetti::ObjectArray<#element, #throwable>)
+                }
+            },
+            TypeSignature::Class(class) => self.emit_class_bare(class, arg, flavor, reject_reasons),
+        })
+    }
+
+    fn emit_class_bare(
+        &mut self,
+        class: &ClassTypeSignature,
+        arg: bool,
+        flavor: &RustTypeFlavor,
+        reject_reasons: &mut Vec<&'static str>,
+    ) -> TokenStream {
+        let java_class = Id(&class.path);
+        let emitted = emitted_class(java_class, self.context, flavor, reject_reasons);
+        let Ok(path) = self.context.java_to_rust_path(emitted, self.mod_) else {
+            reject_reasons.push("ERROR:  Failed to resolve JNI path to Rust path for class type");
+            return TokenStream::new();
+        };
+
+        let params = self.context.class_type_params(emitted.as_str());
+        let exact = emitted == java_class && class.args.len() == params.len();
+        if params.is_empty() || (!exact && !arg) {
+            return path; // the default type arguments
+        }
+
+        let mut args = Vec::new();
+        for i in 0..params.len() {
+            let ty = match class.args.get(i).filter(|_| exact) {
+                Some(TypeArgument::Exact(ty)) => Some(ty),
+                Some(TypeArgument::Extends(ty)) if !arg => Some(ty),
+                _ => None,
+            };
+            // Type arguments that cannot be expressed are erased instead of rejecting the member.
+            let mut arg_reject_reasons = Vec::new();
+            let ty = ty
+                .and_then(|ty| self.emit_bare(ty, arg, flavor, &mut arg_reject_reasons))
+                .filter(|_| arg_reject_reasons.is_empty());
+            args.push(match ty {
+                Some(ty) => ty,
+                None => self.erased(arg),
+            });
+        }
+        quote!(#path<#(#args),*>)
+    }
+
+    fn erased(&mut self, arg: bool) -> TokenStream {
+        if arg {
+            let ident = format_ident!("__T{}", self.fresh.len());
+            self.fresh.push(ident.clone());
+            quote!(#ident)
+        } else {
+            self.context.java_to_rust_path(Id(OBJECT), self.mod_).unwrap()
+        }
+    }
+}
+
+/// Generates the type parameter declarations of an impl block, e.g. `<E: ::java_spaghetti::ReferenceType>`.
+pub(crate) fn emit_impl_generics<'i>(params: impl IntoIterator<Item = &'i Ident>) -> TokenStream {
+    let params: Vec<_> = params.into_iter().collect();
+    if params.is_empty() {
+        return TokenStream::new();
+    }
+    quote!(<#(#params: ::java_spaghetti::ReferenceType),*>)
+}
+
+/// Generates type parameter declarations to follow lifetimes, e.g. `, T: ::java_spaghetti::ReferenceType`.
+pub(crate) fn emit_type_params<'i>(params: impl IntoIterator<Item = &'i Ident>) -> TokenStream {
+    let params = params.into_iter();
+    quote!(#(, #params: ::java_spaghetti::ReferenceType)*)
+}
+
+/// Returns the JNI class name to check an argument against at runtime, see [erased_class_name]. Type variables
+/// are declared as their erased bound in Java, which the Rust signature does not express.
+pub(crate) fn arg_check_class(ty: &TypeSignature, descriptor: &FieldDescriptor, context: &Context) -> Option<String> {
+    if let Some(class_name) = erased_class_name(descriptor, context) {
+        return Some(class_name);
+    }
+    match (ty.element(), &descriptor.field_type) {
+        (TypeSignature::TypeVariable(_), FieldType::Object(class)) if Id::from(class).as_str() != OBJECT => {
+            Some(match descriptor.dimensions {
+                0 => Id::from(class).as_str().to_string(),
+                _ => descriptor.to_string(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if values of the type have to be checked when returned from Java, see the module docs.
+pub(crate) fn needs_return_check(ty: &TypeSignature) -> bool {
+    matches!(ty.element(), TypeSignature::TypeVariable(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{ACC_PUBLIC, ClassFile, Member, WithAttributes, compact, generate, jdk_classes};
+
+    fn classes() -> Vec<ClassFile> {
+        let mut classes = jdk_classes();
+        classes.push(
+            ClassFile::new("java/util/ArrayList")
+                .signature("<E:Ljava/lang/Object;>Ljava/lang/Object;")
+                .method(Member::new(ACC_PUBLIC, "<init>", "()V"))
+                .method(Member::new(ACC_PUBLIC, "size", "()I"))
+                .method(Member::new(ACC_PUBLIC, "get", "(I)Ljava/lang/Object;").signature("(I)TE;"))
+                .method(Member::new(ACC_PUBLIC, "add", "(Ljava/lang/Object;)Z").signature("(TE;)Z")),
+        );
+        classes
+    }
+
+    #[test]
+    fn generic_signatures() {
+        let erased = compact(&generate("", &classes()));
+        let generic = compact(&generate(
+            "rules:\n  - match: \"**\"\n    include: true\n    generics: true\n",
+            &classes(),
+        ));
+
+        // Without `generics`, generic classes are erased.
+        assert!(erased.contains("pubenumArrayList{}"));
+        assert!(erased.contains(
+            "pubfnget<'env>(self:&::java_spaghetti::Ref<'env,Self>,arg0:i32,)->::std::result::Result<\
+             ::std::option::Option<::java_spaghetti::Local<'env,super::lang::Object>>"
+        ));
+        assert!(erased.contains("arg0:impl::java_spaghetti::AsArg<super::lang::Object>,)->::std::result::Result<bool"));
+
+        // With it, type parameters default to `Object` so that the class can still be named without them.
+        assert!(generic.contains(
+            "pubstructArrayList<E=super::lang::Object>(::std::convert::Infallible,::std::marker::PhantomData<(E,)>,);"
+        ));
+        assert!(generic.contains("impl<E:::java_spaghetti::ReferenceType>ArrayList<E>{"));
+        assert!(generic.contains(
+            "pubfnget<'env>(self:&::java_spaghetti::Ref<'env,Self>,arg0:i32,)->::std::result::Result<\
+             ::std::option::Option<::java_spaghetti::Local<'env,E>>"
+        ));
+        assert!(generic.contains("arg0:impl::java_spaghetti::AsArg<E>,)->::std::result::Result<bool"));
+    }
+}
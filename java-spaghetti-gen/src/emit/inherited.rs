@@ -0,0 +1,146 @@
+//! Methods of superclasses and superinterfaces generated on subclasses (see `Rule::inherited_methods`), so that
+//! they can be called without upcasting:
+//!
+//! ```ignore
+//! let name = activity.getClass()?.unwrap().getName()?;
+//! ```
+//!
+//! The methods are looked up on the subclass, JNI resolves them to the inherited declarations.
+
+use std::collections::{HashSet, VecDeque};
+
+use super::classes::{Class, super_classes, type_args};
+use super::methods::Method;
+use crate::config::ClassConfig;
+use crate::emit::Context;
+use crate::parser_util::{ClassTypeSignature, JavaMethod, TypeArgument, TypeSignature};
+
+impl Class {
+    /// Collects the instance methods inherited from generated superclasses and superinterfaces, nearest first,
+    /// leaving out the ones overridden in between. Their signatures refer to the type parameters of this class.
+    pub(crate) fn inherited_methods<'a>(&self, context: &'a Context, cc: &ClassConfig) -> Vec<Method<'a>> {
+        // Bridges don't count, such as those making methods of non-public superclasses public. Methods of generic
+        // superclasses are overridden by methods taking the type arguments instead, e.g. `compareTo(String)`
+        // overrides `compareTo(T)` of `Comparable<String>`, so they're compared by their generic signatures too.
+        let declared: Vec<Method> = (self.java.methods())
+            .map(|method| Method::new(&self.java, method))
+            .filter(|method| !method.java.is_bridge())
+            .map(|mut method| {
+                method.generics = cc.generics;
+                method
+            })
+            .collect();
+        let mut overridden: HashSet<(String, String)> =
+            declared.iter().map(|method| override_key(&method.java)).collect();
+        let mut signatures: Vec<(String, Vec<TypeSignature>)> = (declared.iter())
+            .map(|method| (method.java.name().to_string(), method.signature().args))
+            .collect();
+
+        let path = self.java.path().as_str().to_string();
+        let mut queue = VecDeque::from([ClassTypeSignature {
+            path: path.clone(),
+            args: (self.type_params.iter())
+                .map(|param| TypeArgument::Exact(TypeSignature::TypeVariable(param.clone())))
+                .collect(),
+        }]);
+        let mut visited = HashSet::from([path.clone()]);
+        let mut inherited = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            let Some(class) = context.java_class(&current.path) else {
+                continue;
+            };
+            for super_class in super_classes(class, &current) {
+                if visited.insert(super_class.path.clone()) {
+                    queue.push_back(super_class);
+                }
+            }
+            let Some(generated) = context.all_classes.get(&current.path).filter(|_| current.path != path) else {
+                continue;
+            };
+
+            let super_cc = context.config.resolve_class(&current.path);
+            let type_args = type_args(class, &current);
+            for mut method in generated.members(context, &super_cc).0 {
+                let java = &method.java;
+                if java.is_constructor() || java.is_static() || method.is_static_like() {
+                    continue;
+                }
+                if !overridden.insert(override_key(java)) {
+                    continue;
+                }
+                // Type parameters of the method would shadow the type parameters of this class it refers to.
+                let shadowing = (method.signature().params.iter()).any(|param| self.type_params.contains(param));
+                method.inherit(type_args.as_ref().filter(|_| !shadowing));
+                method.generics = cc.generics;
+                let signature = (method.java.name().to_string(), method.signature().args);
+                if signatures.contains(&signature) {
+                    continue;
+                }
+                signatures.push(signature);
+                method.naming = cc.naming;
+                inherited.push(method);
+            }
+        }
+        inherited
+    }
+}
+
+/// Methods override methods of superclasses with the same name and parameters, the return type may be covariant.
+fn override_key(method: &JavaMethod) -> (String, String) {
+    let descriptor = method.descriptor().to_string();
+    let params = descriptor
+        .split_once(')')
+        .map_or(descriptor.as_str(), |(params, _)| params);
+    (method.name().to_string(), params.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::*;
+
+    #[test]
+    fn overridden_and_colliding() {
+        let string = "()Ljava/lang/String;";
+        let mut classes = jdk_classes();
+        classes.push(
+            ClassFile::new("com/example/Named")
+                .access(ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT)
+                .method(Member::new(ACC_PUBLIC | ACC_ABSTRACT, "getName", string)),
+        );
+        classes.push(
+            ClassFile::new("com/example/Base")
+                .implements("com/example/Named")
+                .method(Member::new(ACC_PUBLIC, "getName", string))
+                .method(Member::new(ACC_PUBLIC, "describe", "()Ljava/lang/Object;"))
+                .method(Member::new(ACC_PUBLIC, "size", "()I"))
+                .method(Member::new(ACC_PUBLIC, "size", "(I)I")),
+        );
+        // `Sub` overrides `describe` with a covariant return type, and overloads `size` once more.
+        classes.push(
+            ClassFile::new("com/example/Sub")
+                .extends("com/example/Base")
+                .implements("com/example/Named")
+                .method(Member::new(ACC_PUBLIC, "describe", string))
+                .method(Member::new(
+                    ACC_PUBLIC | ACC_BRIDGE | ACC_SYNTHETIC,
+                    "describe",
+                    "()Ljava/lang/Object;",
+                ))
+                .method(Member::new(ACC_PUBLIC, "size", "(J)J")),
+        );
+        let yaml = "rules:\n  - match: \"**\"\n    include: true\n    inherited_methods: true\n";
+        let code = generate(yaml, &classes);
+
+        let compact = compact(&code);
+        let sub = &compact[compact.find("pubenumSub{").unwrap()..];
+        let sub = &sub[..1 + sub[1..].find("pubenum").unwrap()];
+        // Each method once: `describe` of `Sub`, `getName` of `Base` and `Named`, and three distinct `size` overloads.
+        for name in ["describe", "getName", "size", "size_int", "size_long"] {
+            let count = sub.matches(&format!("pubfn{name}<'env>")).count();
+            assert_eq!(count, 1, "{name}: {code}");
+        }
+        let describe = "pubfndescribe<'env>(self:&::java_spaghetti::Ref<'env,Self>,)->::std::result::Result<\
+                        ::std::option::Option<::java_spaghetti::Local<'env,super::super::java::lang::String>>";
+        assert!(sub.contains(describe));
+    }
+}
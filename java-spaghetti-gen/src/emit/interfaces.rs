@@ -0,0 +1,205 @@
+//! Rust traits generated for Java interfaces, implemented for every type assignable to the interface, so that code
+//! can be generic over the implementers:
+//!
+//! ```ignore
+//! fn run_all<'env>(tasks: &[&Ref<'env, impl JRunnable>]) -> Result<(), Local<'env, Throwable>> {
+//!     tasks.iter().try_for_each(|task| task.run())
+//! }
+//! ```
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use super::classes::Class;
+use super::fields::RustTypeFlavor;
+use super::generics::{TypeEmitter, emit_type_params};
+use super::methods::Method;
+use super::overloads::ClassGenerics;
+use crate::config::ClassConfig;
+use crate::emit::Context;
+use crate::parser_util::Id;
+
+impl Class {
+    /// Generates a trait with the instance methods of an interface (which should only contain generated methods),
+    /// each calling the method of the interface on the upcasted reference, and a blanket impl for the types
+    /// assignable to it. Returns nothing for classes, or if the name of the trait is taken by another class.
+    pub(crate) fn write_interface_trait(
+        &self,
+        context: &Context,
+        cc: &ClassConfig,
+        class: &ClassGenerics,
+        methods: &[&Method],
+    ) -> anyhow::Result<TokenStream> {
+        if !self.java.is_interface() {
+            return Ok(TokenStream::new());
+        }
+        let trait_name = format!("J{}", self.rust.struct_name);
+        let taken = context
+            .all_classes
+            .values()
+            .any(|class| class.rust.mod_ == self.rust.mod_ && class.rust.struct_name == trait_name);
+        if taken {
+            return Ok(TokenStream::new());
+        }
+
+        let mut trait_methods = TokenStream::new();
+        for method in methods {
+            let java = &method.java;
+            if java.is_constructor() || java.is_static() || method.is_static_like() || method.is_inherited() {
+                continue;
+            }
+            trait_methods.extend(self.emit_trait_method(context, class, method)?);
+        }
+
+        let visibility = match self.java.is_public() || cc.include_private_classes {
+            true => quote!(pub),
+            false => quote!(),
+        };
+        let struct_name = &self.rust.struct_name;
+        let docs = format!(
+            "The methods of [`{struct_name}`] for all types assignable to it, to write code generic over the \
+             implementers of the interface."
+        );
+        let trait_ident = format_ident!("{trait_name}");
+        let self_type = class.self_type;
+        let type_params = class.type_params;
+        let trait_type = quote!(#trait_ident<#(#type_params),*>);
+        // Type arguments default to `Object` like those of the struct, so that `impl JList` can be written.
+        let object = context.java_to_rust_path(Id("java/lang/Object"), &self.rust.mod_)?;
+        let trait_generics = match type_params.is_empty() {
+            true => quote!(),
+            false => quote!(<#(#type_params: ::java_spaghetti::ReferenceType = #object),*>),
+        };
+        let blanket_generics = quote!(
+            <#(#type_params: ::java_spaghetti::ReferenceType,)* __T: ::java_spaghetti::AssignableTo<#self_type>>
+        );
+        Ok(quote!(
+            #[doc = #docs]
+            #visibility trait #trait_ident #trait_generics: ::java_spaghetti::AssignableTo<#self_type> {
+                #trait_methods
+            }
+            impl #blanket_generics #trait_type for __T {}
+        ))
+    }
+
+    /// Generates a default method of the interface trait, calling `method` of the interface.
+    fn emit_trait_method(
+        &self,
+        context: &Context,
+        class: &ClassGenerics,
+        method: &Method,
+    ) -> anyhow::Result<TokenStream> {
+        let descriptor = method.java.descriptor();
+        let signature = method.signature();
+        let mut scope = class.scope.clone();
+        let mut type_params = scope.add(&signature.params, context, &self.rust.mod_)?;
+        let mut types = TypeEmitter::new(context, &scope, &self.rust.mod_);
+        let mut reject_reasons = Vec::new();
+
+        let arg_names: Vec<_> = (method.rust_arg_names().iter())
+            .map(|name| format_ident!("{name}"))
+            .collect();
+        let mut params_decl = quote!(self: &::java_spaghetti::Ref<'env, Self>,);
+        for ((arg, arg_sig), arg_name) in descriptor.parameters.iter().zip(&signature.args).zip(&arg_names) {
+            let (arg_type, _) = types.emit(arg_sig, arg, RustTypeFlavor::ImplAsArg, &mut reject_reasons);
+            params_decl.extend(quote!(#arg_name: #arg_type,));
+        }
+        let ret_decl = method.emit_return_type(&signature, &mut types, &mut reject_reasons).0;
+        if !reject_reasons.is_empty() {
+            return Ok(TokenStream::new());
+        }
+        type_params.append(&mut types.fresh);
+        let turbofish = match type_params.is_empty() {
+            true => quote!(),
+            false => quote!(::<#(#type_params),*>),
+        };
+        let type_params = emit_type_params(&type_params);
+
+        let attributes = match method.java.deprecated() {
+            true => quote!(#[deprecated]),
+            false => quote!(),
+        };
+        let throwable = context.throwable_rust_path(&self.rust.mod_);
+        let method_name = format_ident!("{}", method.rust_name().unwrap());
+        let docs = format!(
+            "Calls [`{}::{}`].",
+            self.rust.struct_name,
+            method_name.to_string().trim_start_matches("r#")
+        );
+        let self_type = class.self_type;
+        Ok(quote!(
+            #[doc = #docs]
+            #attributes
+            fn #method_name<'env #type_params>(#params_decl) -> ::std::result::Result<#ret_decl, ::java_spaghetti::Local<'env, #throwable>> {
+                <#self_type>::#method_name #turbofish(self.upcast_ref(), #(#arg_names),*)
+            }
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::*;
+
+    #[test]
+    fn interface_traits() {
+        let interface = ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT;
+        let mut classes = jdk_classes();
+        classes.push(
+            ClassFile::new("com/example/Task")
+                .access(interface)
+                .method(Member::new(ACC_PUBLIC | ACC_ABSTRACT, "run", "()V"))
+                .method(Member::new(ACC_PUBLIC | ACC_STATIC, "noop", "()Lcom/example/Task;")),
+        );
+        classes.push(
+            ClassFile::new("com/example/Job")
+                .access(interface)
+                .implements("com/example/Task")
+                .method(Member::new(ACC_PUBLIC | ACC_ABSTRACT, "cancel", "()Z")),
+        );
+        classes.push(
+            ClassFile::new("com/example/Source")
+                .access(interface)
+                .signature("<T:Ljava/lang/Object;>Ljava/lang/Object;")
+                .method(Member::new(ACC_PUBLIC | ACC_ABSTRACT, "next", "()Ljava/lang/Object;").signature("()TT;")),
+        );
+        classes.push(
+            ClassFile::new("com/example/Worker")
+                .implements("com/example/Job")
+                .implements("com/example/Source")
+                .signature("Ljava/lang/Object;Lcom/example/Job;Lcom/example/Source<Ljava/lang/String;>;")
+                .method(Member::new(ACC_PUBLIC, "run", "()V"))
+                .method(Member::new(ACC_PUBLIC, "cancel", "()Z"))
+                .method(Member::new(ACC_PUBLIC, "next", "()Ljava/lang/String;")),
+        );
+        let yaml = "rules:\n  - match: \"**\"\n    include: true\n    generics: true\n";
+        let code = generate(yaml, &classes);
+
+        let compact = compact(&code);
+        let task = &compact[compact.find("pubtraitJTask:").unwrap()..];
+        let task = &task[..task.find("implTask{").unwrap()];
+        assert!(
+            task.contains("fnrun<'env>(self:&::java_spaghetti::Ref<'env,Self>,)"),
+            "{code}"
+        );
+        assert!(task.contains("<Task>::run(self.upcast_ref(),)"));
+        assert!(!task.contains("noop"));
+        assert!(task.contains("impl<__T:::java_spaghetti::AssignableTo<Task>>JTask<>for__T{}"));
+        assert!(compact.contains(
+            "pubtraitJSource<T:::java_spaghetti::ReferenceType=super::super::java::lang::Object>:\
+             ::java_spaghetti::AssignableTo<Source<T>>{"
+        ));
+        assert!(compact.contains(
+            "impl<T:::java_spaghetti::ReferenceType,__T:::java_spaghetti::AssignableTo<Source<T>>>JSource<T>for__T{}"
+        ));
+        assert!(!compact.contains("traitJWorker"));
+
+        // `Worker` is assignable to `Task` through `Job`, so the blanket impls cover it.
+        assert!(compact.contains("pubtraitJJob:::java_spaghetti::AssignableTo<Job>{"));
+        for interface in ["Job", "Task", "Source<super::super::java::lang::String>"] {
+            assert!(compact.contains(&format!(
+                "unsafeimpl::java_spaghetti::AssignableTo<{interface}>forWorker{{}}"
+            )));
+        }
+    }
+}
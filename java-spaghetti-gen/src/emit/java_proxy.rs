@@ -11,6 +11,7 @@ use crate::util;
 impl Class {
     pub(crate) fn write_java_proxy(&self, context: &Context) -> anyhow::Result<String> {
         // Collect methods for this class
+        let cc = context.config.resolve_class(self.java.path().as_str());
         let methods: Vec<Method> = self.java.methods().map(|m| Method::new(&self.java, m)).collect();
 
         let java_proxy_path = format!(
@@ -68,7 +69,8 @@ impl Class {
         // Generate methods
         for method in methods {
             let Some(_rust_name) = method.rust_name() else { continue };
-            if method.java.is_static()
+            if !method.is_proxied(&cc)
+                || method.java.is_static()
                 || method.java.is_static_init()
                 || method.java.is_constructor()
                 || method.java.is_final()
@@ -85,10 +87,11 @@ impl Class {
                 ReturnDescriptor::Return(desc) => java_type_name(desc)?,
             };
 
+            let arg_names = method.java_arg_names();
             let mut params = Vec::new();
-            for (i, param) in method.java.descriptor.parameters.iter().enumerate() {
+            for (param, name) in method.java.descriptor.parameters.iter().zip(&arg_names) {
                 let param_type = java_type_name(param)?;
-                params.push(format!("{param_type} arg{i}"));
+                params.push(format!("{param_type} {name}"));
             }
 
             writeln!(w, "    @Override")?;
@@ -103,9 +106,7 @@ impl Class {
             // Method body - call native method
             let native_method_name = format!("native_{method_name}");
             let mut args = vec!["ptr".to_string()];
-            for i in 0..method.java.descriptor.parameters.len() {
-                args.push(format!("arg{i}"));
-            }
+            args.extend(arg_names.iter().cloned());
 
             if return_type == "void" {
                 writeln!(w, "        {}({});", native_method_name, args.join(", "))?;
@@ -116,9 +117,9 @@ impl Class {
 
             // Native method declaration
             let mut native_params = vec!["long ptr".to_string()];
-            for (i, param) in method.java.descriptor.parameters.iter().enumerate() {
+            for (param, name) in method.java.descriptor.parameters.iter().zip(&arg_names) {
                 let param_type = java_type_name(param)?;
-                native_params.push(format!("{param_type} arg{i}"));
+                native_params.push(format!("{param_type} {name}"));
             }
 
             writeln!(
@@ -137,7 +138,7 @@ impl Class {
     }
 }
 
-fn java_type_name(desc: &FieldDescriptor) -> anyhow::Result<String> {
+pub(crate) fn java_type_name(desc: &FieldDescriptor) -> anyhow::Result<String> {
     let mut result = String::new();
 
     let base_type = match &desc.field_type {
@@ -170,7 +171,7 @@ fn java_type_name(desc: &FieldDescriptor) -> anyhow::Result<String> {
 }
 
 pub fn write_java_proxy_files(context: &Context, output_dir: &Path) -> anyhow::Result<()> {
-    for (_, class) in context.all_classes.iter() {
+    for class in context.all_classes.values() {
         let cc = context.config.resolve_class(class.java.path().as_str());
         if !cc.proxy {
             continue;
@@ -0,0 +1,151 @@
+//! Impls of `JavaDisplay`, `JavaEq` and `JavaOrd` (see `Rule::java_display` and `Rule::java_eq`), backed by the
+//! methods of `Object` and `Comparable`:
+//!
+//! ```ignore
+//! println!("{}", locale);
+//! let mut counts: HashMap<JavaKey<Locale>, usize> = HashMap::new();
+//! *counts.entry(JavaKey::new(locale.as_global())).or_default() += 1;
+//! ```
+
+use std::collections::{HashSet, VecDeque};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::classes::{Class, super_classes};
+use super::generics::emit_impl_generics;
+use super::overloads::ClassGenerics;
+use crate::config::ClassConfig;
+use crate::emit::Context;
+use crate::parser_util::{ClassTypeSignature, TypeArgument, TypeSignature};
+
+impl Class {
+    /// Generates the impls of the traits enabled by `cc`. `JavaOrd` is only implemented if the class is comparable to
+    /// itself, see [Self::is_self_comparable].
+    pub(crate) fn write_java_traits(&self, context: &Context, cc: &ClassConfig, class: &ClassGenerics) -> TokenStream {
+        let impl_generics = emit_impl_generics(class.type_params);
+        let self_type = class.self_type;
+        let mut out = TokenStream::new();
+        if cc.java_display {
+            let throwable = context.throwable_rust_path(&self.rust.mod_);
+            out.extend(quote!(
+                /// Panics if `toString()` throws an exception.
+                impl #impl_generics ::java_spaghetti::JavaDisplay for #self_type {
+                    fn fmt(self: &::java_spaghetti::Ref<'_, Self>, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        let string = self.env().get_object_string::<Self, #throwable>(self)
+                            .unwrap_or_else(|_| panic!("exception happened calling Object.toString()"));
+                        f.write_str(&string)
+                    }
+                }
+            ));
+        }
+        if cc.java_eq {
+            out.extend(quote!(
+                impl #impl_generics ::java_spaghetti::JavaEq for #self_type {}
+            ));
+            if self.is_self_comparable(context) {
+                out.extend(quote!(
+                    unsafe impl #impl_generics ::java_spaghetti::JavaOrd for #self_type {}
+                ));
+            }
+        }
+        out
+    }
+
+    /// Whether the class implements `Comparable` for itself or as a raw type, e.g. `Comparable<String>` for `String`
+    /// or `Comparable<E>` of `Enum<E>` for enums.
+    fn is_self_comparable(&self, context: &Context) -> bool {
+        let path = self.java.path().as_str();
+        let mut queue = VecDeque::from([ClassTypeSignature {
+            path: path.to_string(),
+            args: (self.type_params.iter())
+                .map(|param| TypeArgument::Exact(TypeSignature::TypeVariable(param.clone())))
+                .collect(),
+        }]);
+        let mut visited = HashSet::from([path.to_string()]);
+        while let Some(current) = queue.pop_front() {
+            if current.path == "java/lang/Comparable" {
+                return match current.args.as_slice() {
+                    [] => true,
+                    [TypeArgument::Exact(TypeSignature::Class(arg))] => arg.path == path,
+                    _ => false,
+                };
+            }
+            let Some(class) = context.java_class(&current.path) else {
+                continue;
+            };
+            for super_class in super_classes(class, &current) {
+                if visited.insert(super_class.path.clone()) {
+                    queue.push_back(super_class);
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::*;
+
+    #[test]
+    fn java_ord_for_comparable() {
+        let comparable = |name: &str, signature: &str| {
+            ClassFile::new(name)
+                .implements("java/lang/Comparable")
+                .signature(signature)
+        };
+        let mut classes = jdk_classes();
+        classes.push(
+            ClassFile::new("java/lang/Comparable")
+                .access(ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT)
+                .signature("<T:Ljava/lang/Object;>Ljava/lang/Object;"),
+        );
+        classes.push(
+            ClassFile::new("java/lang/Enum")
+                .access(ACC_PUBLIC | ACC_ABSTRACT)
+                .implements("java/lang/Comparable")
+                .signature("<E:Ljava/lang/Enum<TE;>;>Ljava/lang/Object;Ljava/lang/Comparable<TE;>;"),
+        );
+        classes.extend([
+            comparable(
+                "com/example/Version",
+                "Ljava/lang/Object;Ljava/lang/Comparable<Lcom/example/Version;>;",
+            ),
+            // Comparable to its superclass only.
+            ClassFile::new("com/example/Release").extends("com/example/Version"),
+            comparable(
+                "com/example/Name",
+                "Ljava/lang/Object;Ljava/lang/Comparable<Ljava/lang/String;>;",
+            ),
+            ClassFile::new("com/example/Raw").implements("java/lang/Comparable"),
+            ClassFile::new("com/example/Level")
+                .access(ACC_PUBLIC | ACC_FINAL | ACC_ENUM)
+                .extends("java/lang/Enum")
+                .signature("Ljava/lang/Enum<Lcom/example/Level;>;"),
+            ClassFile::new("com/example/Plain"),
+            ClassFile::new("com/example/Opaque"),
+        ]);
+        let yaml = "rules:\n  - match: \"**\"\n    include: true\n    java_eq: true\n  \
+                    - match: \"com/example/Opaque\"\n    java_eq: false\n";
+        let code = generate(yaml, &classes);
+
+        let compact = compact(&code);
+        for (class, ord) in [
+            ("Version", true),
+            ("Release", false),
+            ("Name", false),
+            ("Raw", true),
+            ("Level", true),
+            ("Plain", false),
+        ] {
+            assert!(
+                compact.contains(&format!("impl::java_spaghetti::JavaEqfor{class}{{}}")),
+                "{class}: {code}"
+            );
+            let java_ord = format!("unsafeimpl::java_spaghetti::JavaOrdfor{class}{{}}");
+            assert_eq!(compact.contains(&java_ord), ord, "{class}");
+        }
+        assert!(!compact.contains("JavaEqforOpaque"));
+    }
+}
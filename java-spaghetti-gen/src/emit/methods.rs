@@ -1,20 +1,47 @@
-use cafebabe::descriptors::ReturnDescriptor;
+use std::collections::HashMap;
+
+use cafebabe::descriptors::{FieldDescriptor, ReturnDescriptor};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
 use super::cstring;
-use super::fields::{RustTypeFlavor, emit_fragment_type, emit_type};
+use super::fields::{RustTypeFlavor, emit_arg_check, emit_fragment_type, emit_notes, erased_class_name};
+use super::generics::{TypeEmitter, TypeScope, arg_check_class, emit_type_params, needs_return_check};
+use super::java_proxy::java_type_name;
 use super::known_docs_url::KnownDocsUrl;
 use crate::config::ClassConfig;
 use crate::emit::Context;
-use crate::identifiers::MethodManglingStyle;
-use crate::parser_util::{JavaClass, JavaMethod};
+use crate::identifiers::{MethodManglingStyle, NamingConvention, java_param_names, rust_ident, rust_param_names};
+use crate::parser_util::{
+    JavaClass, JavaMethod, KotlinAccessor, KotlinMethod, MethodSignature, Nullability, TypeSignature,
+};
+use crate::util::Rejection;
 
 pub struct Method<'a> {
     pub class: &'a JavaClass,
     pub java: JavaMethod<'a>,
+    /// The Kotlin declaration of the method, see [crate::parser_util::KotlinMetadata].
+    pub kotlin: Option<KotlinMethod>,
+    /// Set for instance methods of Kotlin `object` declarations, which are generated as static-like functions.
+    pub singleton: Option<KotlinSingleton>,
+    /// Set by `MemberRule::rename`, which replaces the Java name before signatures are appended to colliding names.
+    pub rename: Option<String>,
+    pub naming: NamingConvention,
+    /// Whether the generic signature is used, see `Rule::generics`.
+    pub generics: bool,
     rust_name: Option<String>,
     mangling_style: MethodManglingStyle,
+    /// The signature of a method inherited from a superclass, see [Self::inherit].
+    inherited: Option<MethodSignature>,
+}
+
+/// The static field holding the instance of a Kotlin `object` or `companion object`.
+#[derive(Debug, Clone)]
+pub struct KotlinSingleton {
+    /// JNI path of the class declaring the field, which is the outer class for companion objects.
+    pub class: String,
+    pub field: String,
+    pub descriptor: String,
 }
 
 impl<'a> Method<'a> {
@@ -22,8 +49,14 @@ impl<'a> Method<'a> {
         let mut result = Self {
             class,
             java: JavaMethod::from(java),
+            kotlin: None,
+            singleton: None,
+            rename: None,
+            naming: NamingConvention::Java,
+            generics: false,
             rust_name: None,
             mangling_style: MethodManglingStyle::Java,
+            inherited: None,
         };
         result.set_mangling_style(MethodManglingStyle::Java);
         result
@@ -33,19 +66,94 @@ impl<'a> Method<'a> {
         self.rust_name.as_deref()
     }
 
+    /// Returns the Rust names of the parameters, see [rust_param_names].
+    pub fn rust_arg_names(&self) -> Vec<String> {
+        let names = self.java.parameter_names();
+        rust_param_names(names.as_deref(), self.java.descriptor().parameters.len())
+    }
+
+    /// Returns the Java names of the parameters, see [java_param_names].
+    pub fn java_arg_names(&self) -> Vec<String> {
+        let names = self.java.parameter_names();
+        java_param_names(names.as_deref(), self.java.descriptor().parameters.len())
+    }
+
+    /// Kotlin property accessors are named like the getters and setters of fields, unless they collide.
     pub fn set_mangling_style(&mut self, style: MethodManglingStyle) {
         self.mangling_style = style;
+        if let Some(rename) = &self.rename {
+            // A rename without a descriptor applies to all overloads, which still need their signatures appended.
+            let suffix = self.naming.function(&style.suffix(self.java.descriptor()));
+            self.rust_name = rust_ident(&format!("{rename}{suffix}")).ok();
+            return;
+        }
+        let accessor = self.kotlin.as_ref().and_then(|kotlin| kotlin.accessor.as_ref());
+        let name = match (accessor, style) {
+            (Some(KotlinAccessor::Getter(property)), MethodManglingStyle::Java) => property.clone(),
+            (Some(KotlinAccessor::Setter(property)), MethodManglingStyle::Java) => format!("set_{property}"),
+            _ => self.java.name().to_string(),
+        };
         self.rust_name = self
             .mangling_style
-            .mangle(self.java.name(), self.java.descriptor())
+            .mangle(&name, self.java.descriptor(), self.naming)
             .ok()
     }
 
-    pub fn emit(&self, context: &Context, cc: &ClassConfig, mod_: &str) -> anyhow::Result<TokenStream> {
+    /// Whether the proxy of the class overrides the method, unless excluded by a `MemberRule`.
+    pub fn is_proxied(&self, cc: &ClassConfig) -> bool {
+        let member = cc.method(self.java.name(), &self.java.descriptor().to_string());
+        member.proxy.unwrap_or(true) && member.include != Some(false)
+    }
+
+    /// Marks the method as inherited by a subclass (see `Rule::inherited_methods`), with the type arguments of the
+    /// superclass declaring it substituted into its signature. Without type arguments, e.g. for raw superclasses, the
+    /// erased signature is used.
+    pub fn inherit(&mut self, type_args: Option<&HashMap<String, TypeSignature>>) {
+        let signature = self.signature();
+        self.inherited = Some(match type_args {
+            Some(vars) => {
+                // Type parameters of the method shadow those of the class.
+                let vars = (vars.iter())
+                    .filter(|(name, _)| !signature.params.contains(name))
+                    .map(|(name, ty)| (name.clone(), ty.clone()))
+                    .collect();
+                MethodSignature {
+                    args: signature.args.iter().map(|arg| arg.substitute(&vars)).collect(),
+                    ret: signature.ret.as_ref().map(|ret| ret.substitute(&vars)),
+                    params: signature.params,
+                }
+            }
+            None => MethodSignature::erased(self.java.descriptor()),
+        });
+    }
+
+    pub fn is_inherited(&self) -> bool {
+        self.inherited.is_some()
+    }
+
+    pub fn is_static_like(&self) -> bool {
+        self.singleton.is_some() && !self.java.is_static() && !self.java.is_constructor()
+    }
+
+    pub fn emit(
+        &self,
+        context: &Context,
+        cc: &ClassConfig,
+        class_scope: &TypeScope,
+        mod_: &str,
+    ) -> anyhow::Result<TokenStream> {
         let mut emit_reject_reasons = Vec::new();
 
         let descriptor = self.java.descriptor();
 
+        let signature = self.signature();
+        let mut scope = match self.java.is_static() {
+            false => class_scope.clone(),
+            true => TypeScope::default(),
+        };
+        let mut type_params = scope.add(&signature.params, context, mod_)?;
+        let mut types = TypeEmitter::new(context, &scope, mod_);
+
         let method_name = if let Some(name) = self.rust_name() {
             name.to_owned()
         } else {
@@ -60,37 +168,63 @@ impl<'a> Method<'a> {
             emit_reject_reasons.push("Static class constructor - never needs to be called by Rust.");
         }
 
-        // Parameter names may or may not be available as extra debug information.  Example:
-        // https://docs.oracle.com/javase/tutorial/reflect/member/methodparameterreflection.html
-
         let mut params_array = TokenStream::new(); // Contents of let __jni_args = [...];
 
         // Contents of fn name<'env>(...) {
-        let mut params_decl = if self.java.is_constructor() || self.java.is_static() {
+        let mut params_decl = if self.java.is_constructor() || self.java.is_static() || self.is_static_like() {
             quote!(__jni_env: ::java_spaghetti::Env<'env>,)
         } else {
             quote!(self: &::java_spaghetti::Ref<'env, Self>,)
         };
 
-        for (arg_idx, arg) in descriptor.parameters.iter().enumerate() {
-            let arg_name = format_ident!("arg{}", arg_idx);
-            let arg_type = emit_type(arg, context, mod_, RustTypeFlavor::ImplAsArg, &mut emit_reject_reasons)?;
+        let mut arg_checks = TokenStream::new();
+        let mut notes = Vec::new();
+
+        let arg_names = self.rust_arg_names();
+        let mut arg_nullability = self.java.parameter_nullability();
+        if let Some(kotlin) = &self.kotlin {
+            // Kotlin doesn't declare the outer instance taken by constructors of inner classes.
+            let offset = arg_nullability.len().saturating_sub(kotlin.params.len());
+            for (nullability, kotlin) in arg_nullability[offset..].iter_mut().zip(&kotlin.params) {
+                *nullability = kotlin.or(*nullability);
+            }
+        }
+        for (((arg, arg_sig), arg_name), nullability) in
+            (descriptor.parameters.iter().zip(&signature.args).zip(&arg_names)).zip(arg_nullability)
+        {
+            let arg_name = format_ident!("{arg_name}");
+            let (arg_type, arg_sig) = types.emit(arg_sig, arg, RustTypeFlavor::ImplAsArg, &mut emit_reject_reasons);
+
+            if let Some(class_name) = arg_check_class(&arg_sig, arg, context) {
+                arg_checks.extend(emit_arg_check(&arg_name, &class_name));
+                notes.push(format!(
+                    "`{arg_name}` must be a `{}`, which is checked at runtime.",
+                    java_type_name(arg)?
+                ));
+            }
+
+            if emit_fragment_type(arg) == "object" {
+                match nullability {
+                    Nullability::NonNull => notes.push(format!("`{arg_name}` must not be null.")),
+                    Nullability::Nullable => notes.push(format!("`{arg_name}` may be null.")),
+                    Nullability::Unknown => {}
+                }
+            }
 
             params_array.extend(quote!(::java_spaghetti::AsJValue::as_jvalue(&#arg_name),));
             params_decl.extend(quote!(#arg_name: #arg_type,));
         }
 
-        let mut ret_decl = if let ReturnDescriptor::Return(desc) = &descriptor.return_type {
-            emit_type(
-                desc,
-                context,
-                mod_,
-                RustTypeFlavor::OptionLocal,
-                &mut emit_reject_reasons,
-            )?
-        } else {
-            quote!(())
-        };
+        let (mut ret_decl, ret_check, ret_non_null) =
+            self.emit_return_type(&signature, &mut types, &mut emit_reject_reasons);
+        type_params.append(&mut types.fresh);
+        let type_params = emit_type_params(&type_params);
+
+        if let ReturnDescriptor::Return(desc) = &descriptor.return_type
+            && erased_class_name(desc, context).is_some()
+        {
+            notes.push(format!("Returns a `{}`.", java_type_name(desc)?));
+        }
 
         let mut ret_method_fragment = if let ReturnDescriptor::Return(desc) = &descriptor.return_type {
             emit_fragment_type(desc)
@@ -108,7 +242,20 @@ impl<'a> Method<'a> {
         }
 
         if !emit_reject_reasons.is_empty() {
-            // TODO log
+            // Inherited methods are reported with the class declaring them.
+            if self.is_inherited() {
+                return Ok(TokenStream::new());
+            }
+            context.report.lock().unwrap().reject(
+                self.class.path().as_str(),
+                Rejection {
+                    kind: "method",
+                    name: self.java.name().to_string(),
+                    descriptor: self.java.descriptor().to_string(),
+                    signature: self.java_signature()?,
+                    reasons: emit_reject_reasons,
+                },
+            );
             return Ok(TokenStream::new());
         }
 
@@ -124,10 +271,18 @@ impl<'a> Method<'a> {
             Some(url) => format!("{url}"),
             None => self.java.name().to_string(),
         };
+        if let Some(javadoc) =
+            context
+                .javadoc
+                .method(self.class.path().as_str(), self.java.name(), self.java.descriptor())
+        {
+            notes.insert(0, javadoc.render(context, mod_));
+        }
+        let notes = emit_notes(&notes);
 
         let throwable = context.throwable_rust_path(mod_);
 
-        let env_let = match !self.java.is_constructor() && !self.java.is_static() {
+        let env_let = match !self.java.is_constructor() && !self.java.is_static() && !self.is_static_like() {
             true => quote!(let __jni_env = self.env();),
             false => quote!(),
         };
@@ -140,26 +295,42 @@ impl<'a> Method<'a> {
         let descriptor = cstring(&self.java.descriptor().to_string());
         let method_name = format_ident!("{method_name}");
 
-        let call = if self.java.is_constructor() {
+        let mut call = if self.java.is_constructor() {
             quote!(__jni_env.new_object_a(__jni_class, __jni_method, __jni_args))
         } else if self.java.is_static() {
             let call = format_ident!("call_static_{ret_method_fragment}_method_a");
             quote!(    __jni_env.#call(__jni_class, __jni_method, __jni_args))
+        } else if let Some(singleton) = self.singleton.as_ref().filter(|_| self.is_static_like()) {
+            let call = format_ident!("call_{ret_method_fragment}_method_a");
+            let receiver = emit_singleton(singleton, &throwable);
+            quote!({
+                let __jni_receiver: ::java_spaghetti::Local<'env, Self> = #receiver;
+                __jni_env.#call(&__jni_receiver, __jni_method, __jni_args)
+            })
         } else {
             let call = format_ident!("call_{ret_method_fragment}_method_a");
             quote!(    __jni_env.#call(self, __jni_method, __jni_args))
         };
+        if ret_check {
+            call = quote!(__jni_env.require_return_instance_of(#call));
+        }
+        if ret_non_null {
+            // Annotations are not enforced by Java, a null value is returned as a `NullPointerException`.
+            call = quote!(__jni_env.require_non_null(#call));
+        }
 
         // XXX: use `OnceLock::get_or_try_init` when it becomes stable.
         out.extend(quote!(
             #[doc = #docs]
+            #notes
             #attributes
-            pub fn #method_name<'env>(#params_decl) -> ::std::result::Result<#ret_decl, ::java_spaghetti::Local<'env, #throwable>> {
+            pub fn #method_name<'env #type_params>(#params_decl) -> ::std::result::Result<#ret_decl, ::java_spaghetti::Local<'env, #throwable>> {
                 use ::java_spaghetti::ReferenceType;
                 static __METHOD: ::std::sync::OnceLock<::java_spaghetti::JMethodID> = ::std::sync::OnceLock::new();
                 unsafe {
                     let __jni_args = &[#params_array];
                     #env_let
+                    #arg_checks
                     let __jni_class = Self::jni_get_class(__jni_env).unwrap();
                     let __jni_method = if let Some(&__jni_method) = __METHOD.get() {
                         __jni_method
@@ -174,4 +345,269 @@ impl<'a> Method<'a> {
 
         Ok(out)
     }
+
+    /// Generates `name` for a varargs method, which takes the trailing arguments as a slice of primitives or an
+    /// iterator of references, and calls the method (which must have been emitted) with an array of them.
+    pub fn emit_varargs(
+        &self,
+        context: &Context,
+        class_scope: &TypeScope,
+        mod_: &str,
+        name: &str,
+    ) -> anyhow::Result<TokenStream> {
+        let descriptor = self.java.descriptor();
+        let signature = self.signature();
+        let (Some((varargs, params)), Some((TypeSignature::Array(varargs_sig), params_sig))) =
+            (descriptor.parameters.split_last(), signature.args.split_last())
+        else {
+            return Ok(TokenStream::new());
+        };
+        if varargs.dimensions == 0 {
+            return Ok(TokenStream::new());
+        }
+        let element = FieldDescriptor {
+            dimensions: varargs.dimensions - 1,
+            field_type: varargs.field_type.clone(),
+        };
+
+        let mut scope = match self.java.is_static() {
+            false => class_scope.clone(),
+            true => TypeScope::default(),
+        };
+        let mut type_params = scope.add(&signature.params, context, mod_)?;
+        let mut types = TypeEmitter::new(context, &scope, mod_);
+        let mut reject_reasons = Vec::new();
+
+        let static_like = self.java.is_constructor() || self.java.is_static() || self.is_static_like();
+        let mut params_decl = match static_like {
+            true => quote!(__jni_env: ::java_spaghetti::Env<'env>,),
+            false => quote!(self: &::java_spaghetti::Ref<'env, Self>,),
+        };
+        let arg_names: Vec<_> = self
+            .rust_arg_names()
+            .iter()
+            .map(|name| format_ident!("{name}"))
+            .collect();
+        for ((arg, arg_sig), arg_name) in params.iter().zip(params_sig).zip(&arg_names) {
+            let (arg_type, _) = types.emit(arg_sig, arg, RustTypeFlavor::ImplAsArg, &mut reject_reasons);
+            params_decl.extend(quote!(#arg_name: #arg_type,));
+        }
+
+        let throwable = context.throwable_rust_path(mod_);
+        let varargs_name = arg_names.last().unwrap();
+        let (element_type, element_sig) = types.emit_arg_type(varargs_sig, &element, &mut reject_reasons);
+        let array = match element_sig {
+            TypeSignature::Primitive(_) => {
+                let (array_type, _) = types.emit_arg_type(
+                    &TypeSignature::Array(Box::new(element_sig)),
+                    varargs,
+                    &mut reject_reasons,
+                );
+                params_decl.extend(quote!(#varargs_name: &[#element_type]));
+                quote!(<#array_type as ::java_spaghetti::PrimitiveArray<#element_type>>::new_from(__jni_env, #varargs_name))
+            }
+            _ => {
+                params_decl.extend(quote!(
+                    #varargs_name: impl ::std::iter::IntoIterator<
+                        Item: ::java_spaghetti::AsArg<#element_type>,
+                        IntoIter: ::std::iter::ExactSizeIterator,
+                    >
+                ));
+                quote!(::java_spaghetti::ObjectArray::<#element_type, #throwable>::new_from(__jni_env, #varargs_name.into_iter()))
+            }
+        };
+
+        let ret_decl = match self.java.is_constructor() {
+            true => quote!(::java_spaghetti::Local<'env, Self>),
+            false => self.emit_return_type(&signature, &mut types, &mut reject_reasons).0,
+        };
+        if !reject_reasons.is_empty() {
+            return Ok(TokenStream::new());
+        }
+        type_params.append(&mut types.fresh);
+        let turbofish = match type_params.is_empty() {
+            true => quote!(),
+            false => quote!(::<#(#type_params),*>),
+        };
+        let type_params = emit_type_params(&type_params);
+
+        let attributes = match self.java.deprecated() {
+            true => quote!(#[deprecated]),
+            false => quote!(),
+        };
+        let method_name = format_ident!("{}", self.rust_name().unwrap());
+        let docs = format!(
+            "Calls [`Self::{}`] with the trailing `{}...` arguments collected into an array.",
+            method_name.to_string().trim_start_matches("r#"),
+            java_type_name(&element)?
+        );
+        let name = format_ident!("{name}");
+        let args = &arg_names[..params.len()];
+        let (env_let, call) = match static_like {
+            true => (
+                quote!(),
+                quote!(Self::#method_name #turbofish(__jni_env, #(#args,)* &#varargs_name)),
+            ),
+            false => (
+                quote!(let __jni_env = self.env();),
+                quote!(self.#method_name #turbofish(#(#args,)* &#varargs_name)),
+            ),
+        };
+        Ok(quote!(
+            #[doc = #docs]
+            #attributes
+            pub fn #name<'env #type_params>(#params_decl) -> ::std::result::Result<#ret_decl, ::java_spaghetti::Local<'env, #throwable>> {
+                #env_let
+                let #varargs_name = #array;
+                #call
+            }
+        ))
+    }
+
+    /// Returns the generic signature, or the erased one where it is missing or generics are disabled. Synthetic
+    /// parameters of inner class and enum constructors are missing from the generic signature.
+    pub fn signature(&self) -> MethodSignature {
+        let descriptor = self.java.descriptor();
+        if !self.generics {
+            return MethodSignature::erased(descriptor);
+        }
+        if let Some(inherited) = &self.inherited {
+            return inherited.clone();
+        }
+        (self.java.signature())
+            .filter(|sig| sig.args.len() == descriptor.parameters.len())
+            .unwrap_or_else(|| MethodSignature::erased(descriptor))
+    }
+
+    /// Returns the Rust return type, whether the returned value has to be checked at runtime (see
+    /// [needs_return_check]), and whether it is declared non-null.
+    pub fn emit_return_type(
+        &self,
+        signature: &MethodSignature,
+        types: &mut TypeEmitter,
+        reject_reasons: &mut Vec<&'static str>,
+    ) -> (TokenStream, bool, bool) {
+        let ReturnDescriptor::Return(desc) = &self.java.descriptor().return_type else {
+            return (quote!(()), false, false);
+        };
+        let ret_sig = signature.ret.clone().unwrap_or_else(|| desc.into());
+        let mut nullability = self.java.return_nullability();
+        if let Some(kotlin) = &self.kotlin {
+            nullability = kotlin.returns.or(nullability);
+        }
+        let non_null = emit_fragment_type(desc) == "object" && nullability == Nullability::NonNull;
+        let flavor = match non_null {
+            false => RustTypeFlavor::OptionLocal,
+            true => RustTypeFlavor::Local,
+        };
+        let (ret_type, ret_sig) = types.emit(&ret_sig, desc, flavor, reject_reasons);
+        (ret_type, needs_return_check(&ret_sig), non_null)
+    }
+
+    /// Returns the Java declaration of the method, e.g. `java.lang.String substring(int, int)`.
+    pub fn java_signature(&self) -> anyhow::Result<String> {
+        if self.java.is_static_init() {
+            return Ok("static {}".to_string());
+        }
+
+        let descriptor = self.java.descriptor();
+        let params = descriptor
+            .parameters
+            .iter()
+            .map(java_type_name)
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .join(", ");
+
+        if self.java.is_constructor() {
+            let class = self.class.path().as_str();
+            let simple_name = class.rsplit('/').next().unwrap_or(class).replace('$', ".");
+            return Ok(format!("{simple_name}({params})"));
+        }
+
+        let ret = match &descriptor.return_type {
+            ReturnDescriptor::Return(desc) => java_type_name(desc)?,
+            ReturnDescriptor::Void => "void".to_string(),
+        };
+        let static_ = if self.java.is_static() { "static " } else { "" };
+        Ok(format!("{static_}{ret} {}({params})", self.java.name()))
+    }
+}
+
+/// Generates an expression reading the instance of a Kotlin singleton, returning a `NullPointerException` from the
+/// method if it is null.
+fn emit_singleton(singleton: &KotlinSingleton, throwable: &TokenStream) -> TokenStream {
+    let class = cstring(&singleton.class);
+    let field = cstring(&singleton.field);
+    let descriptor = cstring(&singleton.descriptor);
+    quote!({
+        static __CLASS: ::std::sync::OnceLock<::java_spaghetti::JClass> = ::std::sync::OnceLock::new();
+        static __FIELD: ::std::sync::OnceLock<::java_spaghetti::JFieldID> = ::std::sync::OnceLock::new();
+        let __jni_class = __CLASS.get_or_init(|| __jni_env.require_class(#class).unwrap());
+        let __jni_field = *__FIELD.get_or_init(|| __jni_env.require_static_field(__jni_class, #field, #descriptor));
+        __jni_env.require_non_null::<Self, #throwable>(Ok(__jni_env.get_static_object_field(__jni_class, __jni_field)))?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::*;
+
+    #[test]
+    fn varargs() {
+        let mut classes = jdk_classes();
+        classes.push(
+            ClassFile::new("com/example/Format")
+                .method(Member::new(ACC_PUBLIC | ACC_VARARGS, "<init>", "([J)V"))
+                .method(Member::new(
+                    ACC_PUBLIC | ACC_STATIC | ACC_VARARGS,
+                    "format",
+                    "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/String;",
+                ))
+                .method(Member::new(ACC_PUBLIC | ACC_VARARGS, "sum", "([I)I"))
+                .method(Member::new(ACC_PUBLIC | ACC_VARARGS, "max", "([I)I"))
+                // Takes the name of the variant of `max`.
+                .method(Member::new(ACC_PUBLIC, "max_varargs", "()V")),
+        );
+        let code = generate("", &classes);
+
+        let compact = compact(&code);
+        assert!(compact.contains("pubfnnew_varargs<'env>(__jni_env:::java_spaghetti::Env<'env>,arg0:&[i64])"));
+        assert!(compact.contains("<::java_spaghetti::LongArrayas::java_spaghetti::PrimitiveArray<i64>>::new_from"));
+        assert!(compact.contains("pubfnsum_varargs<'env>(self:&::java_spaghetti::Ref<'env,Self>,arg0:&[i32])"));
+        assert!(compact.contains(
+            "arg1:impl::std::iter::IntoIterator<Item:::java_spaghetti::AsArg<super::super::java::lang::Object>,\
+             IntoIter:::std::iter::ExactSizeIterator,>"
+        ));
+        assert!(compact.contains("Self::format(__jni_env,arg0,&arg1)"), "{code}");
+        assert_eq!(compact.matches("fnmax_varargs").count(), 1);
+    }
+
+    #[test]
+    fn renamed_overloads() {
+        let mut classes = jdk_classes();
+        classes.push(
+            ClassFile::new("com/example/Store")
+                .method(Member::new(ACC_PUBLIC, "get", "(I)Ljava/lang/Object;"))
+                .method(Member::new(ACC_PUBLIC, "get", "(Ljava/lang/String;)Ljava/lang/Object;"))
+                .method(Member::new(ACC_PUBLIC, "list", "()[Ljava/lang/Object;"))
+                .method(Member::new(ACC_PUBLIC, "list", "(I)[Ljava/lang/Object;")),
+        );
+        let yaml = r#"
+rules:
+  - match: "**"
+    include: true
+    naming: snake_case
+    methods:
+      - name: get
+        rename: fetch
+      - name: list
+        descriptor: "()[Ljava/lang/Object;"
+        rename: listAll
+"#;
+        let compact = compact(&generate(yaml, &classes));
+        assert!(compact.contains("pubfnfetch_int<'env>(self:&::java_spaghetti::Ref<'env,Self>,arg0:i32,)"));
+        assert!(compact.contains("pubfnfetch_string<'env>(self:&::java_spaghetti::Ref<'env,Self>"));
+        assert!(compact.contains("pubfnlistAll<'env>(self:&::java_spaghetti::Ref<'env,Self>,)"));
+        assert!(compact.contains("pubfnlist<'env>(self:&::java_spaghetti::Ref<'env,Self>,arg0:i32,)"));
+    }
 }
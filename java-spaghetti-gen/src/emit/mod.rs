@@ -2,16 +2,24 @@
 
 mod class_proxy;
 mod classes;
+mod dependencies;
+mod enums;
 mod fields;
+mod generics;
+mod inherited;
+mod interfaces;
 pub mod java_proxy;
+mod java_traits;
 mod known_docs_url;
 mod methods;
 mod modules;
+mod overloads;
 mod preamble;
 
 use std::collections::HashMap;
 use std::ffi::CString;
 use std::io;
+use std::path::Path;
 use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::Mutex;
@@ -20,16 +28,27 @@ use std::time::Duration;
 use proc_macro2::{Literal, TokenStream};
 use quote::{TokenStreamExt, format_ident, quote};
 
-use self::classes::Class;
+pub(crate) use self::classes::Class;
+pub(crate) use self::dependencies::dependencies_of;
 use self::modules::Module;
-use self::preamble::write_preamble;
-use crate::{config, parser_util, util};
+use self::preamble::{outer_attributes, write_preamble};
+use crate::{cache, config, javadoc, parser_util, util};
 
 pub struct Context<'a> {
     pub(crate) config: &'a config::Config,
     pub(crate) module: Module,
     pub(crate) all_classes: HashMap<String, Rc<Class>>,
+    /// Classes known for type resolution and inheritance, but not generated. See `Rule::reference_only`.
+    pub(crate) reference_classes: HashMap<String, parser_util::JavaClass>,
+    /// Classes not included by any rule, kept for `Rule::include_dependencies` and `Config::degrade_missing_types`.
+    pub(crate) excluded_classes: HashMap<String, parser_util::JavaClass>,
     pub(crate) progress: Mutex<util::Progress>,
+    /// Members skipped while writing, see `Config::report`.
+    pub(crate) report: Mutex<util::Report>,
+    /// Descriptions read from `Config::sources`.
+    pub(crate) javadoc: javadoc::Javadoc,
+    /// Code generated by the previous run, see `Config::cache`.
+    pub(crate) cache: Option<Mutex<cache::Cache>>,
 }
 
 impl<'a> Context<'a> {
@@ -38,9 +57,14 @@ impl<'a> Context<'a> {
             config,
             module: Default::default(),
             all_classes: HashMap::new(),
+            reference_classes: HashMap::new(),
+            excluded_classes: HashMap::new(),
             progress: Mutex::new(util::Progress::with_duration(Duration::from_millis(
                 if config.logging_verbose { 0 } else { 300 },
             ))),
+            report: Mutex::new(util::Report::default()),
+            javadoc: javadoc::Javadoc::default(),
+            cache: None,
         }
     }
 
@@ -84,14 +108,83 @@ impl<'a> Context<'a> {
         Ok(res)
     }
 
+    /// Returns the parsed class, whether it is generated or not.
+    pub(crate) fn java_class(&self, java_class: &str) -> Option<&parser_util::JavaClass> {
+        match self.all_classes.get(java_class) {
+            Some(class) => Some(&class.java),
+            None => self
+                .reference_classes
+                .get(java_class)
+                .or_else(|| self.excluded_classes.get(java_class)),
+        }
+    }
+
+    /// Returns the generated class to use in signatures for `java_class`: itself if it is generated, otherwise
+    /// its nearest generated superclass if `Config::degrade_missing_types` is enabled, or `Config::reference_fallback`.
+    ///
+    /// Returns `None` for classes that are neither generated, reference-only nor degraded.
+    pub(crate) fn emitted_class_for<'c>(&'c self, java_class: parser_util::Id<'c>) -> Option<parser_util::Id<'c>> {
+        if self.all_classes.contains_key(java_class.as_str()) {
+            return Some(java_class);
+        }
+        let superclass = match self.config.degrade_missing_types {
+            true => self.nearest_emitted_superclass(java_class),
+            false if self.reference_classes.contains_key(java_class.as_str()) => None,
+            false => return None,
+        };
+        let fallback = parser_util::Id(&self.config.reference_fallback);
+        let emitted = superclass.or_else(|| self.all_classes.contains_key(fallback.as_str()).then_some(fallback));
+        if let Some(emitted) = emitted {
+            let mut report = self.report.lock().unwrap();
+            if self.java_class(java_class.as_str()).is_none() {
+                report.reject_class(java_class.as_str(), "not found in the inputs".to_string());
+            }
+            report.reject_class(
+                java_class.as_str(),
+                format!("replaced by {} in signatures", emitted.as_str()),
+            );
+        }
+        emitted
+    }
+
+    /// Walks the superclasses of a class that is not generated, as far as they are parsed.
+    fn nearest_emitted_superclass<'c>(&'c self, java_class: parser_util::Id<'c>) -> Option<parser_util::Id<'c>> {
+        let mut class = self.java_class(java_class.as_str())?;
+        loop {
+            let superclass = class.super_path()?;
+            if self.all_classes.contains_key(superclass.as_str()) {
+                return Some(superclass);
+            }
+            class = self.java_class(superclass.as_str())?;
+        }
+    }
+
     pub fn add_class(&mut self, class: parser_util::JavaClass) -> Result<(), anyhow::Error> {
         let cc = self.config.resolve_class(class.path().as_str());
         if !cc.include {
+            let reason = match cc.reference_only {
+                true => "reference only",
+                false => "not included by the rules",
+            };
+            self.report
+                .lock()
+                .unwrap()
+                .reject_class(class.path().as_str(), reason.to_string());
+            if cc.reference_only {
+                self.reference_classes.insert(class.path().as_str().to_string(), class);
+            } else if self.config.includes_dependencies() || self.config.degrade_missing_types {
+                self.excluded_classes.insert(class.path().as_str().to_string(), class);
+            }
             return Ok(());
         }
 
+        self.insert_class(class)
+    }
+
+    fn insert_class(&mut self, class: parser_util::JavaClass) -> Result<(), anyhow::Error> {
         let java_path = class.path().as_str().to_string();
-        let s = Rc::new(Class::new(class)?);
+        let generics = self.config.resolve_class(&java_path).generics;
+        let s = Rc::new(Class::new(class, generics)?);
 
         self.all_classes.insert(java_path, s.clone());
 
@@ -100,7 +193,7 @@ impl<'a> Context<'a> {
             rust_mod = rust_mod.modules.entry(fragment.to_owned()).or_default();
         }
         if rust_mod.classes.contains_key(&s.rust.struct_name) {
-            return io_data_err!(
+            io_data_err!(
                 "Unable to add_class(): java class name {:?} was already added",
                 &s.rust.struct_name
             )?;
@@ -111,11 +204,196 @@ impl<'a> Context<'a> {
     }
 
     pub fn write(&self, out: &mut impl io::Write) -> anyhow::Result<()> {
-        write_preamble(out)?;
-        self.module.write(self, out)
+        let include = self.config.output_layout == config::OutputLayout::Include;
+        write_preamble(out, self, include)?;
+        let attributes = match include {
+            true => outer_attributes(),
+            false => String::new(),
+        };
+        self.module.write(self, &attributes, out)
+    }
+
+    /// Writes a `mod.rs` per Rust module into `dir`, see `OutputLayout::PerPackage`.
+    pub fn write_dir(&self, dir: &Path) -> anyhow::Result<()> {
+        self.module.write_dir(self, dir, true)
     }
 }
 
 fn cstring(s: &str) -> Literal {
     Literal::c_string(&CString::from_str(s).unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::*;
+
+    #[test]
+    fn reference_only() {
+        let out = generate(
+            r#"
+rules:
+  - match: ["java/lang/Object", "com/example/Activity"]
+    include: true
+  - match: "com/example/Intent"
+    reference_only: true
+"#,
+            &[
+                ClassFile::new("java/lang/Object"),
+                ClassFile::new("com/example/Intent"),
+                ClassFile::new("com/example/Activity")
+                    .method(Member::new(ACC_PUBLIC, "startActivity", "(Lcom/example/Intent;)V"))
+                    .method(Member::new(ACC_PUBLIC, "getIntent", "()Lcom/example/Intent;")),
+            ],
+        );
+        let out = compact(&out);
+        assert!(!out.contains("pubenumIntent"));
+
+        // Arguments of reference-only types are checked at runtime, return values are the fallback type.
+        assert!(out.contains("arg0:impl::java_spaghetti::AsArg<super::super::java::lang::Object>"));
+        assert!(out.contains(r#"require_arg_instance_of(&arg0,c"com/example/Intent",&__CLASS)?;"#));
+        assert!(out.contains(
+            "pubfngetIntent<'env>(self:&::java_spaghetti::Ref<'env,Self>,)->::std::result::Result<\
+             ::std::option::Option<::java_spaghetti::Local<'env,super::super::java::lang::Object>>"
+        ));
+    }
+
+    #[test]
+    fn report_rejected_classes() {
+        let config = config(
+            r#"
+degrade_missing_types: true
+rules:
+  - match: ["java/lang/Object", "com/example/Base", "com/example/Holder"]
+    include: true
+  - match: "com/example/Intent"
+    reference_only: true
+"#,
+        );
+        let context = context(
+            &config,
+            &[
+                ClassFile::new("java/lang/Object"),
+                ClassFile::new("com/example/Base"),
+                ClassFile::new("com/example/Hidden").extends("com/example/Base"),
+                ClassFile::new("com/example/Intent"),
+                ClassFile::new("com/example/Unused"),
+                ClassFile::new("com/example/Holder")
+                    .method(Member::new(ACC_PUBLIC, "hidden", "()Lcom/example/Hidden;"))
+                    .method(Member::new(ACC_PUBLIC, "intent", "()Lcom/example/Intent;"))
+                    .method(Member::new(ACC_PUBLIC, "missing", "()Lcom/example/Missing;")),
+            ],
+        );
+        context.write(&mut Vec::new()).unwrap();
+
+        let report = context.report.lock().unwrap().to_text();
+        let rejected = &report[report.find("rejected class").unwrap()..];
+        assert_eq!(
+            rejected,
+            "rejected class com/example/Hidden
+    - not included by the rules
+    - replaced by com/example/Base in signatures
+rejected class com/example/Intent
+    - reference only
+    - replaced by java/lang/Object in signatures
+rejected class com/example/Missing
+    - not found in the inputs
+    - replaced by java/lang/Object in signatures
+rejected class com/example/Unused
+    - not included by the rules
+"
+        );
+    }
+
+    /// Builds code generated with most options enabled, and using it, with `cargo check`. This is slow and needs the
+    /// toolchain of the runtime, other tests only check the generated tokens.
+    #[test]
+    #[ignore = "runs cargo check on the generated code"]
+    fn generated_code_compiles() {
+        let interface = ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT;
+        let constant = ACC_PUBLIC | ACC_STATIC | ACC_FINAL | ACC_ENUM;
+        let mut classes = jdk_classes();
+        classes.extend([
+            ClassFile::new("java/lang/Comparable")
+                .access(interface)
+                .signature("<T:Ljava/lang/Object;>Ljava/lang/Object;"),
+            ClassFile::new("java/lang/Enum")
+                .access(ACC_PUBLIC | ACC_ABSTRACT)
+                .implements("java/lang/Comparable")
+                .signature("<E:Ljava/lang/Enum<TE;>;>Ljava/lang/Object;Ljava/lang/Comparable<TE;>;"),
+            ClassFile::new("java/util/ArrayList")
+                .signature("<E:Ljava/lang/Object;>Ljava/lang/Object;")
+                .method(Member::new(ACC_PUBLIC, "<init>", "()V"))
+                .method(Member::new(ACC_PUBLIC, "get", "(I)Ljava/lang/Object;").signature("(I)TE;"))
+                .method(Member::new(ACC_PUBLIC, "add", "(Ljava/lang/Object;)Z").signature("(TE;)Z")),
+            ClassFile::new("com/example/Op")
+                .access(ACC_PUBLIC | ACC_ABSTRACT | ACC_ENUM)
+                .extends("java/lang/Enum")
+                .signature("Ljava/lang/Enum<Lcom/example/Op;>;")
+                .field(Member::new(constant, "PLUS", "Lcom/example/Op;"))
+                .field(Member::new(constant, "MINUS", "Lcom/example/Op;")),
+            ClassFile::new("com/example/Format")
+                .method(Member::new(
+                    ACC_PUBLIC | ACC_STATIC | ACC_VARARGS,
+                    "format",
+                    "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/String;",
+                ))
+                .method(Member::new(ACC_PUBLIC | ACC_STATIC | ACC_VARARGS, "sum", "([I)I")),
+            ClassFile::new("com/example/Task").access(interface).method(Member::new(
+                ACC_PUBLIC | ACC_ABSTRACT,
+                "run",
+                "()V",
+            )),
+            ClassFile::new("com/example/Base").method(Member::new(ACC_PUBLIC, "getName", "()Ljava/lang/String;")),
+            ClassFile::new("com/example/Worker")
+                .extends("com/example/Base")
+                .implements("com/example/Task")
+                .implements("java/lang/Comparable")
+                .signature("Lcom/example/Base;Lcom/example/Task;Ljava/lang/Comparable<Lcom/example/Worker;>;")
+                .field(Member::new(ACC_PUBLIC, "label", "Ljava/lang/String;"))
+                .method(Member::new(ACC_PUBLIC, "run", "()V")),
+        ]);
+        let erased = generate("", &classes);
+        let yaml = "rules:\n  - match: \"**\"\n    include: true\n    generics: true\n    inherited_methods: true\n    \
+                    java_display: true\n    java_eq: true\n";
+        let bindings = generate(yaml, &classes);
+
+        let lib = r#"
+            use std::collections::{BTreeSet, HashSet};
+
+            use bindings::com::example::{Format, JTask, Op, OpEnum, Worker};
+            use bindings::java::lang::{String, Throwable};
+            use bindings::java::util::ArrayList;
+            use java_spaghetti::{Env, JavaKey, Local, Ref};
+
+            fn run_all<'env>(tasks: &[&Ref<'env, impl JTask>]) -> Result<(), Local<'env, Throwable>> {
+                tasks.iter().try_for_each(|task| task.run())
+            }
+
+            // Without `generics`, generic classes are used without type arguments.
+            pub fn erased(env: Env<'_>) {
+                let list = erased::java::util::ArrayList::new(env).unwrap();
+                list.add(&erased::java::lang::String::from_env_str(env, "item")).unwrap();
+            }
+
+            pub fn calls<'env>(env: Env<'env>, worker: &Ref<'env, Worker>) -> Result<(), Local<'env, Throwable>> {
+                let list = ArrayList::<String>::new(env)?;
+                list.add(&String::from_env_str(env, "item"))?;
+                let _: Option<Local<'env, String>> = list.get(0)?;
+
+                assert_eq!(Op::from_rust(env, OpEnum::PLUS).to_rust(), Some(OpEnum::PLUS));
+                Format::format_varargs(env, &String::from_env_str(env, "%s %s"), [worker, worker])?;
+                Format::sum_varargs(env, &[1, 2])?;
+
+                run_all(&[worker])?;
+                let _: Option<Local<'env, String>> = worker.getName()?;
+                worker.set_label(&String::from_env_str(env, "label"))?;
+                println!("{worker}");
+
+                let _: HashSet<JavaKey<Worker>> = HashSet::from([JavaKey::new(worker.as_global())]);
+                let _: BTreeSet<JavaKey<Worker>> = BTreeSet::from([JavaKey::new(worker.as_global())]);
+                Ok(())
+            }
+        "#;
+        check_crate("generated_code", &[("erased", erased), ("bindings", bindings)], lib).unwrap();
+    }
+}
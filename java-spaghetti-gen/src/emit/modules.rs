@@ -2,12 +2,16 @@ use core::fmt;
 use std::collections::BTreeMap;
 use std::fmt::Write;
 use std::io;
+use std::path::Path;
 use std::rc::Rc;
 
 use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::{format_ident, quote};
 
 use super::classes::Class;
+use super::preamble::write_preamble;
 use crate::emit::Context;
+use crate::util;
 
 #[derive(Debug, Default)]
 pub(crate) struct Module {
@@ -17,31 +21,110 @@ pub(crate) struct Module {
 }
 
 impl Module {
-    pub(crate) fn write(&self, context: &Context, out: &mut impl io::Write) -> anyhow::Result<()> {
+    /// Writes the module contents, with `attributes` before each submodule.
+    pub(crate) fn write(&self, context: &Context, attributes: &str, out: &mut impl io::Write) -> anyhow::Result<()> {
+        if context.config.pretty_print {
+            let attributes = attributes.parse().map_err(|e| anyhow::anyhow!("{e}"))?;
+            let tokens = self.tokens(context, &attributes)?;
+            out.write_all(pretty_print(tokens)?.as_bytes())?;
+            return Ok(());
+        }
+
         for (name, module) in self.modules.iter() {
             writeln!(out)?;
 
+            write!(out, "{attributes}")?;
             writeln!(out, "pub mod {name} {{")?;
-            module.write(context, out)?;
+            module.write(context, "", out)?;
             writeln!(out, "}}")?;
         }
 
-        for (_, class) in self.classes.iter() {
-            let res = class.write(context)?;
-            out.write_all(dumb_format(res).as_bytes())?;
+        self.write_classes(context, out)
+    }
+
+    /// Writes the module to `dir/mod.rs` and its submodules to subdirectories of `dir`.
+    pub(crate) fn write_dir(&self, context: &Context, dir: &Path, root: bool) -> anyhow::Result<()> {
+        let mut out = Vec::with_capacity(4096);
+        self.write_mod_rs(context, root, &mut out)?;
+        util::write_generated(context, &dir.join("mod.rs"), &out[..])?;
+
+        for (name, module) in self.modules.iter() {
+            // `pub mod r#type;` is read from `type/mod.rs`.
+            module.write_dir(context, &dir.join(name.trim_start_matches("r#")), false)?;
+        }
+        Ok(())
+    }
+
+    fn write_mod_rs(&self, context: &Context, root: bool, out: &mut impl io::Write) -> anyhow::Result<()> {
+        if root {
+            write_preamble(out, context, false)?;
+        }
+        if context.config.pretty_print {
+            let mut tokens = TokenStream::new();
+            for name in self.modules.keys() {
+                let name = format_ident!("{name}");
+                tokens.extend(quote!(pub mod #name;));
+            }
+            for class in self.classes.values() {
+                tokens.extend(class_tokens(context, class)?);
+            }
+            out.write_all(pretty_print(tokens)?.as_bytes())?;
+            return Ok(());
+        }
+
+        for name in self.modules.keys() {
+            writeln!(out, "pub mod {name};")?;
+        }
+        self.write_classes(context, out)
+    }
+
+    /// Returns the module contents for `pretty_print`, with `attributes` before each submodule.
+    fn tokens(&self, context: &Context, attributes: &TokenStream) -> anyhow::Result<TokenStream> {
+        let mut tokens = TokenStream::new();
+        for (name, module) in self.modules.iter() {
+            let name = format_ident!("{name}");
+            let contents = module.tokens(context, &TokenStream::new())?;
+            tokens.extend(quote!(#attributes pub mod #name { #contents }));
+        }
+        for class in self.classes.values() {
+            tokens.extend(class_tokens(context, class)?);
         }
+        Ok(tokens)
+    }
 
+    fn write_classes(&self, context: &Context, out: &mut impl io::Write) -> anyhow::Result<()> {
+        for class in self.classes.values() {
+            out.write_all(class_code(context, class)?.as_bytes())?;
+        }
         Ok(())
     }
 }
 
+fn class_code(context: &Context, class: &Class) -> anyhow::Result<String> {
+    context.cached_class(class, || Ok(dumb_format(class.write(context)?)))
+}
+
+/// Returns the tokens of a class for `pretty_print`, parsed from its cached code with `Config::cache`.
+fn class_tokens(context: &Context, class: &Class) -> anyhow::Result<TokenStream> {
+    match context.cache {
+        None => class.write(context),
+        Some(_) => class_code(context, class)?.parse().map_err(|e| anyhow::anyhow!("{e}")),
+    }
+}
+
+/// Formats items like `rustfmt` would, mostly.
+pub(super) fn pretty_print(tokens: TokenStream) -> anyhow::Result<String> {
+    let file: syn::File = syn::parse2(tokens)?;
+    Ok(prettyplease::unparse(&file))
+}
+
 /// Convert tokenstream to string, doing a best-effort formatting
 /// inserting newlines at `;` and `{}`.
 ///
 /// The user is supposed to run the output through `rustfmt`, this is
 /// intended just to prevent the output from being a single huge line
 /// to make debugging syntax errors easier.
-fn dumb_format(ts: TokenStream) -> String {
+pub(super) fn dumb_format(ts: TokenStream) -> String {
     let mut f = DumbFormatter {
         space: false,
         after_newline: true,
@@ -137,3 +220,110 @@ impl DumbFormatter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::pretty_print;
+    use crate::testing::{ACC_PUBLIC, ClassFile, Member, TempDir, config, context, generate, jdk_classes};
+
+    fn classes() -> Vec<ClassFile> {
+        let mut classes = jdk_classes();
+        classes.push(ClassFile::new("com/example/Activity").method(Member::new(
+            ACC_PUBLIC,
+            "setTitle",
+            "(Ljava/lang/String;)V",
+        )));
+        classes.push(ClassFile::new("com/example/type/Widget"));
+        classes
+    }
+
+    #[test]
+    fn per_package_tree() {
+        let config = config("output_layout: per_package\n");
+        let dir = TempDir::new("per-package-tree");
+        context(&config, &classes()).write_dir(dir.path()).unwrap();
+
+        let read = |path: &str| fs::read_to_string(dir.path().join(path)).unwrap();
+        let root = read("mod.rs");
+        assert!(root.contains("mod util"));
+        assert!(root.contains("pub mod com;") && root.contains("pub mod java;"));
+        assert!(read("com/mod.rs").contains("pub mod example;"));
+        let example = read("com/example/mod.rs");
+        assert!(example.contains("pub mod r#type;"));
+        assert!(example.contains("pub enum Activity"));
+        assert!(read("com/example/type/mod.rs").contains("pub enum Widget"));
+        assert!(read("java/lang/mod.rs").contains("pub enum Throwable"));
+    }
+
+    #[test]
+    fn per_package_markers() {
+        let config = config("output_layout: per_package\n");
+        let context = context(&config, &classes());
+        let dir = TempDir::new("per-package-markers");
+
+        // Files without the marker are not overwritten, even if other files of the tree are generated.
+        let example = dir.path().join("com/example/mod.rs");
+        fs::create_dir_all(example.parent().unwrap()).unwrap();
+        fs::write(&example, "// hand-written\n").unwrap();
+        let err = context.write_dir(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("Cannot overwrite"), "{err}");
+        assert_eq!(fs::read_to_string(&example).unwrap(), "// hand-written\n");
+        let root = fs::read_to_string(dir.path().join("mod.rs")).unwrap();
+        assert!(root.starts_with("// WARNING:  This file was autogenerated"));
+
+        // Each generated file has the marker, so generating again replaces them.
+        fs::remove_file(&example).unwrap();
+        context.write_dir(dir.path()).unwrap();
+        for path in ["mod.rs", "com/mod.rs", "com/example/mod.rs", "com/example/type/mod.rs"] {
+            let code = fs::read_to_string(dir.path().join(path)).unwrap();
+            assert!(code.starts_with("// WARNING:  This file was autogenerated"), "{path}");
+        }
+        context.write_dir(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn pretty_print_stable() {
+        let code = generate("pretty_print: true\n", &classes());
+        assert_eq!(code, generate("pretty_print: true\n", &classes()));
+
+        // The modules after the preamble are formatted as `prettyplease` would format them again.
+        let modules = &code[code.find("pub mod com {").unwrap()..];
+        assert_eq!(pretty_print(modules.parse().unwrap()).unwrap(), modules);
+
+        let expected = r#"
+        impl Activity {
+            ///setTitle
+            pub fn setTitle<'env>(
+                self: &::java_spaghetti::Ref<'env, Self>,
+                arg0: impl ::java_spaghetti::AsArg<super::super::java::lang::String>,
+            ) -> ::std::result::Result<
+                (),
+                ::java_spaghetti::Local<'env, super::super::java::lang::Throwable>,
+            > {
+                use ::java_spaghetti::ReferenceType;
+                static __METHOD: ::std::sync::OnceLock<::java_spaghetti::JMethodID> = ::std::sync::OnceLock::new();
+                unsafe {
+                    let __jni_args = &[::java_spaghetti::AsJValue::as_jvalue(&arg0)];
+                    let __jni_env = self.env();
+                    let __jni_class = Self::jni_get_class(__jni_env).unwrap();
+                    let __jni_method = if let Some(&__jni_method) = __METHOD.get() {
+                        __jni_method
+                    } else {
+                        let __jni_method = __jni_env
+                            .require_method(
+                                __jni_class,
+                                c"setTitle",
+                                c"(Ljava/lang/String;)V",
+                            )?;
+                        *__METHOD.get_or_init(|| __jni_method)
+                    };
+                    __jni_env.call_void_method_a(self, __jni_method, __jni_args)
+                }
+            }
+        }
+"#;
+        assert!(code.contains(expected), "{code}");
+    }
+}
@@ -0,0 +1,317 @@
+//! Overload sets generated as a single method, generic over a trait implemented by the argument tuples of the
+//! overloads (see `Rule::generic_overloads`). The tuple types pick the overload at compile time:
+//!
+//! ```ignore
+//! sb.append((&string,))?;
+//! sb.append((b'x' as u16,))?;
+//! sb.append((&chars, 0, 3))?;
+//! ```
+//!
+//! References select the overload declared with exactly their Java type, see `java_spaghetti::ArgType`.
+
+use std::collections::{BTreeMap, HashSet};
+
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use super::classes::Class;
+use super::generics::{TypeEmitter, TypeScope, emit_type_params};
+use super::methods::Method;
+use crate::config::ClassConfig;
+use crate::emit::Context;
+use crate::identifiers::MethodManglingStyle;
+use crate::parser_util::TypeSignature;
+
+/// The generated overload sets of a class.
+#[derive(Default)]
+pub(crate) struct OverloadSets {
+    /// The argument traits, next to the struct of the class.
+    pub traits: TokenStream,
+    /// Methods for the impl of the class.
+    pub methods: TokenStream,
+    /// Methods for the impl with the default type arguments, see `Class::write`.
+    pub static_methods: TokenStream,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Receiver {
+    Instance,
+    Static,
+    Constructor,
+}
+
+/// The type of a parameter of an overload, see [overlap].
+#[derive(Debug, PartialEq, Eq)]
+enum ArgKey {
+    Type(String),
+    /// Types with type variables of the method, which are wrapped in a tuple to keep them apart from other types.
+    Wrapped(String),
+    /// A type variable of the class, which is wrapped too. It isn't pinned by the type parameters of the method, so
+    /// it may stand for the type of another wrapped parameter.
+    ClassVariable,
+}
+
+/// Whether the impls for the parameters of two overloads overlap, in which case the set can't be generated.
+fn overlap(a: &[ArgKey], b: &[ArgKey]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|pair| match pair {
+            (ArgKey::ClassVariable, ArgKey::ClassVariable | ArgKey::Wrapped(_)) => true,
+            (ArgKey::Wrapped(_), ArgKey::ClassVariable) => true,
+            (a, b) => a == b,
+        })
+}
+
+/// The type parameters of the class the overloads are generated for.
+pub(crate) struct ClassGenerics<'a> {
+    /// The struct with its type parameters, e.g. `List<E>`.
+    pub self_type: &'a TokenStream,
+    pub type_params: &'a [Ident],
+    pub scope: &'a TypeScope,
+}
+
+impl Class {
+    /// Generates a method for each set of overloaded `methods` (which should only contain generated methods), in
+    /// addition to the methods named after their signatures. Sets are skipped if their name is taken by another
+    /// member, or if overloads have the same Rust argument types.
+    pub(crate) fn write_overloads(
+        &self,
+        context: &Context,
+        cc: &ClassConfig,
+        class: &ClassGenerics,
+        methods: &[&Method],
+        mut taken: HashSet<String>,
+    ) -> anyhow::Result<OverloadSets> {
+        let mut sets: BTreeMap<(&str, Receiver), Vec<&Method>> = BTreeMap::new();
+        for &method in methods {
+            if method.rust_name().is_none() || method.is_static_like() {
+                continue;
+            }
+            let receiver = if method.java.is_constructor() {
+                Receiver::Constructor
+            } else if method.java.is_static() {
+                Receiver::Static
+            } else {
+                Receiver::Instance
+            };
+            sets.entry((method.java.name(), receiver)).or_default().push(method);
+        }
+
+        let visibility = match self.java.is_public() || cc.include_private_classes {
+            true => quote!(pub),
+            false => quote!(),
+        };
+        let self_type = class.self_type;
+        let mut out = OverloadSets::default();
+        for ((java_name, receiver), overloads) in sets {
+            if overloads.len() < 2 {
+                continue;
+            }
+            let descriptor = overloads[0].java.descriptor();
+            let Ok(name) = MethodManglingStyle::Java.mangle(java_name, descriptor, cc.naming) else {
+                continue;
+            };
+            if !taken.insert(name.clone()) {
+                continue;
+            }
+            let trait_name = format_ident!("{}_{}_Args", self.rust.struct_name, name.trim_start_matches("r#"));
+
+            let mut keys = Vec::new();
+            let mut impls = TokenStream::new();
+            let mut docs = Vec::new();
+            for method in &overloads {
+                let Some((key, tokens)) = self.emit_overload_impl(context, class, method, receiver, &trait_name)?
+                else {
+                    continue;
+                };
+                keys.push(key);
+                impls.extend(tokens);
+                docs.push(format!(
+                    "- `{}`: `{}`",
+                    method.java_signature()?,
+                    method.rust_name().unwrap_or_default()
+                ));
+            }
+            let overlapping = (keys.iter().enumerate()).any(|(i, a)| keys[i + 1..].iter().any(|b| overlap(a, b)));
+            if keys.len() < 2 || keys.len() != docs.len() || overlapping {
+                continue;
+            }
+
+            let class_params = match receiver {
+                Receiver::Static => &[],
+                _ => class.type_params,
+            };
+            let class_generics = emit_type_params(class_params);
+            let (receiver_decl, receiver_param, receiver_arg) = match receiver {
+                Receiver::Instance => (
+                    quote!(self: &::java_spaghetti::Ref<'env, Self>),
+                    quote!(this: &::java_spaghetti::Ref<'env, #self_type>),
+                    quote!(self),
+                ),
+                _ => (
+                    quote!(__jni_env: ::java_spaghetti::Env<'env>),
+                    quote!(__jni_env: ::java_spaghetti::Env<'env>),
+                    quote!(__jni_env),
+                ),
+            };
+            let trait_docs = format!(
+                "Argument tuples of the overloads of `{}::{name}`.",
+                self.rust.struct_name
+            );
+            out.traits.extend(quote!(
+                #[doc = #trait_docs]
+                #visibility trait #trait_name<'env #class_generics, __S> {
+                    type Output;
+                    fn jni_call_overload(self, #receiver_param) -> Self::Output;
+                }
+                #impls
+            ));
+
+            let method_docs = format!(
+                "Calls the overload of `{java_name}` taking the types of `args`, a tuple with an argument for each \
+                 parameter. References must have the exact Java type of the parameter, see \
+                 `java_spaghetti::ArgType`.\n\n{}",
+                docs.join("\n")
+            );
+            let trait_type = quote!(#trait_name<'env #(, #class_params)*, __S>);
+            let name = format_ident!("{name}");
+            let method = quote!(
+                #[doc = #method_docs]
+                pub fn #name<'env, __S, __A: #trait_type>(#receiver_decl, args: __A) -> <__A as #trait_type>::Output {
+                    args.jni_call_overload(#receiver_arg)
+                }
+            );
+            match receiver == Receiver::Static && !class.type_params.is_empty() {
+                true => out.static_methods.extend(method),
+                false => out.methods.extend(method),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Generates the impl of the argument trait for the argument tuple of `method`. Returns the types of the
+    /// parameters, to tell whether the impls of overloads overlap, or `None` if a type can't be generated.
+    fn emit_overload_impl(
+        &self,
+        context: &Context,
+        class: &ClassGenerics,
+        method: &Method,
+        receiver: Receiver,
+        trait_name: &Ident,
+    ) -> anyhow::Result<Option<(Vec<ArgKey>, TokenStream)>> {
+        let mod_ = &self.rust.mod_;
+        let descriptor = method.java.descriptor();
+        let signature = method.signature();
+        let mut scope = match receiver {
+            Receiver::Static => TypeScope::default(),
+            _ => class.scope.clone(),
+        };
+        let method_params = scope.add(&signature.params, context, mod_)?;
+        let mut types = TypeEmitter::new(context, &scope, mod_);
+        let mut reject_reasons = Vec::new();
+
+        let mut sig = Vec::new();
+        let mut key = Vec::new();
+        let mut tuple = Vec::new();
+        let mut bounds = Vec::new();
+        let mut args = Vec::new();
+        for (i, (arg, arg_sig)) in descriptor.parameters.iter().zip(&signature.args).enumerate() {
+            let (ty, arg_sig) = types.emit_arg_type(arg_sig, arg, &mut reject_reasons);
+            let index = Literal::usize_unsuffixed(i);
+            args.push(quote!(self.#index));
+            if let TypeSignature::Primitive(_) = arg_sig {
+                key.push(ArgKey::Type(ty.to_string()));
+                sig.push(ty.clone());
+                tuple.push(ty);
+                continue;
+            }
+            let arg_type = format_ident!("__A{i}");
+            bounds.push(quote!(#arg_type: ::java_spaghetti::ArgType<Type = #ty> + ::java_spaghetti::AsArg<#ty>));
+            tuple.push(quote!(#arg_type));
+            // Type variables may stand for the type of another overload, so they're wrapped to keep impls apart.
+            match (&arg_sig, arg_sig.element()) {
+                (TypeSignature::TypeVariable(var), _) if !signature.params.contains(var) => {
+                    key.push(ArgKey::ClassVariable);
+                    sig.push(quote!((#ty,)));
+                }
+                (_, TypeSignature::TypeVariable(_)) => {
+                    key.push(ArgKey::Wrapped(ty.to_string()));
+                    sig.push(quote!((#ty,)));
+                }
+                _ => {
+                    key.push(ArgKey::Type(ty.to_string()));
+                    sig.push(ty);
+                }
+            }
+        }
+        let fresh = std::mem::take(&mut types.fresh);
+        let self_type = class.self_type;
+        let ret = match receiver {
+            Receiver::Constructor => quote!(::java_spaghetti::Local<'env, #self_type>),
+            _ => method.emit_return_type(&signature, &mut types, &mut reject_reasons).0,
+        };
+        if !reject_reasons.is_empty() {
+            return Ok(None);
+        }
+
+        let class_params = match receiver {
+            Receiver::Static => &[],
+            _ => class.type_params,
+        };
+        let generics = emit_type_params(class_params.iter().chain(&method_params).chain(&fresh));
+        let throwable = context.throwable_rust_path(mod_);
+        let name = format_ident!("{}", method.rust_name().unwrap());
+        let call_params: Vec<_> = method_params.iter().chain(&fresh).collect();
+        let turbofish = match call_params.is_empty() {
+            true => quote!(),
+            false => quote!(::<#(#call_params),*>),
+        };
+        let class_name = format_ident!("{}", self.rust.struct_name);
+        let (receiver_param, call) = match receiver {
+            Receiver::Instance => (
+                quote!(this: &::java_spaghetti::Ref<'env, #self_type>),
+                quote!(this.#name #turbofish(#(#args),*)),
+            ),
+            Receiver::Static => (
+                quote!(__jni_env: ::java_spaghetti::Env<'env>),
+                quote!(<#class_name>::#name #turbofish(__jni_env #(, #args)*)),
+            ),
+            Receiver::Constructor => (
+                quote!(__jni_env: ::java_spaghetti::Env<'env>),
+                quote!(<#self_type>::#name #turbofish(__jni_env #(, #args)*)),
+            ),
+        };
+
+        let sig = quote!(((#(#sig,)*), (#(#method_params,)*)));
+        let tokens = quote!(
+            impl<'env #generics #(, #bounds)*> #trait_name<'env #(, #class_params)*, #sig> for (#(#tuple,)*) {
+                type Output = ::std::result::Result<#ret, ::java_spaghetti::Local<'env, #throwable>>;
+                fn jni_call_overload(self, #receiver_param) -> Self::Output {
+                    #call
+                }
+            }
+        );
+        key.push(ArgKey::Type(method_params.len().to_string()));
+        Ok(Some((key, tokens)))
+    }
+}
+
+#[test]
+fn overlap_test() {
+    use ArgKey::*;
+
+    let ty = |s: &str| Type(s.to_string());
+    let wrapped = |s: &str| Wrapped(s.to_string());
+    assert!(overlap(&[ty("i32"), ty("0")], &[ty("i32"), ty("0")]));
+    assert!(!overlap(&[ty("i32"), ty("0")], &[ty("i64"), ty("0")]));
+    assert!(!overlap(&[ty("i32"), ty("0")], &[ty("i32"), ty("1")]));
+    assert!(!overlap(&[wrapped("T"), ty("1")], &[ty("String"), ty("1")]));
+    assert!(!overlap(
+        &[wrapped("T"), ty("1")],
+        &[wrapped("ObjectArray<T>"), ty("1")]
+    ));
+    assert!(overlap(
+        &[ClassVariable, ty("0")],
+        &[wrapped("ObjectArray<T>"), ty("0")]
+    ));
+    assert!(!overlap(&[ClassVariable, ty("0")], &[ty("String"), ty("0")]));
+}
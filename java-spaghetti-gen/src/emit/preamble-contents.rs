@@ -1,12 +1,3 @@
-#![allow(unused_imports)]
-#![allow(non_camel_case_types)] // We map Java inner classes to Outer_Inner
-#![allow(dead_code)] // We generate structs for private Java types too, just in case.
-#![allow(deprecated)] // We're generating deprecated types/methods
-#![allow(non_upper_case_globals)] // We might be generating Java style fields/methods
-#![allow(non_snake_case)] // We might be generating Java style fields/methods
-#![allow(clippy::all)] // we don't ensure generated bindings are clippy-compliant at all.
-#![allow(unsafe_code)] // play nice if user has `deny(unsafe_code)` in their crate.
-
 mod util {
     use std::char::DecodeUtf16Error;
     use std::fmt;
@@ -16,66 +7,6 @@ mod util {
 
     use super::java::lang::{String as JString, Throwable};
 
-    impl JavaDebug for Throwable {
-        fn fmt(self: &Ref<'_, Self>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            writeln!(f, "java::lang::Throwable")?;
-
-            match self.getMessage() {
-                Ok(Some(message)) => writeln!(f, "    getMessage:            {:?}", message)?,
-                Ok(None) => writeln!(f, "    getMessage:            N/A (returned null)")?,
-                Err(_) => writeln!(f, "    getMessage:            N/A (threw an exception!)")?,
-            }
-
-            match self.getLocalizedMessage() {
-                Ok(Some(message)) => writeln!(f, "    getLocalizedMessage:   {:?}", message)?,
-                Ok(None) => writeln!(f, "    getLocalizedMessage:   N/A (returned null)")?,
-                Err(_) => writeln!(f, "    getLocalizedMessage:   N/A (threw an exception!)")?,
-            }
-
-            match self.getStackTrace() {
-                Err(_) => writeln!(f, "    getStackTrace:         N/A (threw an exception!)")?,
-                Ok(None) => writeln!(f, "    getStackTrace:         N/A (returned null)")?,
-                Ok(Some(stack_trace)) => {
-                    writeln!(f, "    getStackTrace:")?;
-                    for frame in stack_trace.iter() {
-                        match frame {
-                            None => writeln!(f, "        N/A (frame was null)")?,
-                            Some(frame) => {
-                                let file_line = match (frame.getFileName(), frame.getLineNumber()) {
-                                    (Ok(Some(file)), Ok(line)) => {
-                                        format!("{}({}):", file.to_string_lossy(), line)
-                                    }
-                                    (Ok(Some(file)), _) => format!("{}:", file.to_string_lossy()),
-                                    (_, _) => "N/A (getFileName threw an exception or returned null)".to_owned(),
-                                };
-
-                                let class_method = match (frame.getClassName(), frame.getMethodName()) {
-                                    (Ok(Some(class)), Ok(Some(method))) => {
-                                        format!("{}.{}", class.to_string_lossy(), method.to_string_lossy())
-                                    }
-                                    (Ok(Some(class)), _) => class.to_string_lossy(),
-                                    (_, Ok(Some(method))) => method.to_string_lossy(),
-                                    (_, _) => "N/A (getClassName + getMethodName threw exceptions or returned null)"
-                                        .to_owned(),
-                                };
-
-                                writeln!(f, "        {:120}{}", file_line, class_method)?;
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Consider also dumping:
-            // API level 1+:
-            //      getCause()
-            // API level 19+:
-            //      getSuppressed()
-
-            Ok(())
-        }
-    }
-
     impl JString {
         /// Create new local string from an Env + AsRef<str>
         pub fn from_env_str<'env, S: AsRef<str>>(env: Env<'env>, string: S) -> Local<'env, Self> {
@@ -1,8 +1,139 @@
-use std::io::{self, Write};
+use std::io::Write;
 
-pub fn write_preamble(out: &mut impl Write) -> io::Result<()> {
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+use super::modules::{dumb_format, pretty_print};
+use crate::emit::Context;
+
+/// Lints allowed in the generated code, as inner attributes.
+const ATTRIBUTES: &str = include_str!("preamble-attributes.rs");
+
+/// Writes the preamble. With `OutputLayout::Include`, the lints are allowed by outer attributes on each top-level
+/// module instead, see [`outer_attributes`].
+pub fn write_preamble(out: &mut impl Write, context: &Context, include: bool) -> anyhow::Result<()> {
+    match include {
+        false => writeln!(out, "{ATTRIBUTES}")?,
+        true => write!(out, "{}", outer_attributes())?,
+    }
     write!(out, "{}", include_str!("preamble-contents.rs"))?;
     writeln!(out)?;
+    let throwable_debug = throwable_debug(context)?;
+    match context.config.pretty_print {
+        true => write!(out, "{}", pretty_print(throwable_debug)?)?,
+        false => write!(out, "{}", dumb_format(throwable_debug))?,
+    }
+    writeln!(out)?;
     writeln!(out)?;
     Ok(())
 }
+
+/// Returns the ident of a generated method called by the preamble, as named by the rules and disambiguated against
+/// the other members of its class.
+fn method_ident(context: &Context, class: &str, name: &str, descriptor: &str) -> anyhow::Result<Ident> {
+    let rust_name = match context.all_classes.get(class) {
+        Some(class) => class.method_rust_name(context, name, descriptor)?,
+        None => None,
+    };
+    Ok(format_ident!("{}", rust_name.as_deref().unwrap_or(name)))
+}
+
+/// Generates the `JavaDebug` impl of `Throwable`, which prints the message and the stack trace.
+fn throwable_debug(context: &Context) -> anyhow::Result<TokenStream> {
+    let string = "()Ljava/lang/String;";
+    let throwable = |name, descriptor| method_ident(context, "java/lang/Throwable", name, descriptor);
+    let get_message = throwable("getMessage", string)?;
+    let get_localized_message = throwable("getLocalizedMessage", string)?;
+    let get_stack_trace = throwable("getStackTrace", "()[Ljava/lang/StackTraceElement;")?;
+    let frame = |name, descriptor| method_ident(context, "java/lang/StackTraceElement", name, descriptor);
+    let get_file_name = frame("getFileName", string)?;
+    let get_line_number = frame("getLineNumber", "()I")?;
+    let get_class_name = frame("getClassName", string)?;
+    let get_method_name = frame("getMethodName", string)?;
+
+    Ok(quote! {
+        impl ::java_spaghetti::JavaDebug for java::lang::Throwable {
+            fn fmt(self: &::java_spaghetti::Ref<'_, Self>, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                writeln!(f, "java::lang::Throwable")?;
+
+                match self.#get_message() {
+                    Ok(Some(message)) => writeln!(f, "    getMessage:            {:?}", message)?,
+                    Ok(None) => writeln!(f, "    getMessage:            N/A (returned null)")?,
+                    Err(_) => writeln!(f, "    getMessage:            N/A (threw an exception!)")?,
+                }
+
+                match self.#get_localized_message() {
+                    Ok(Some(message)) => writeln!(f, "    getLocalizedMessage:   {:?}", message)?,
+                    Ok(None) => writeln!(f, "    getLocalizedMessage:   N/A (returned null)")?,
+                    Err(_) => writeln!(f, "    getLocalizedMessage:   N/A (threw an exception!)")?,
+                }
+
+                match self.#get_stack_trace() {
+                    Err(_) => writeln!(f, "    getStackTrace:         N/A (threw an exception!)")?,
+                    Ok(None) => writeln!(f, "    getStackTrace:         N/A (returned null)")?,
+                    Ok(Some(stack_trace)) => {
+                        writeln!(f, "    getStackTrace:")?;
+                        for frame in stack_trace.iter() {
+                            match frame {
+                                None => writeln!(f, "        N/A (frame was null)")?,
+                                Some(frame) => {
+                                    let file_line = match (frame.#get_file_name(), frame.#get_line_number()) {
+                                        (Ok(Some(file)), Ok(line)) => format!("{}({}):", file.to_string_lossy(), line),
+                                        (Ok(Some(file)), _) => format!("{}:", file.to_string_lossy()),
+                                        (_, _) => "N/A (getFileName threw an exception or returned null)".to_owned(),
+                                    };
+
+                                    let class_method = match (frame.#get_class_name(), frame.#get_method_name()) {
+                                        (Ok(Some(class)), Ok(Some(method))) => {
+                                            format!("{}.{}", class.to_string_lossy(), method.to_string_lossy())
+                                        }
+                                        (Ok(Some(class)), _) => class.to_string_lossy(),
+                                        (_, Ok(Some(method))) => method.to_string_lossy(),
+                                        (_, _) => "N/A (getClassName + getMethodName threw exceptions or returned null)".to_owned(),
+                                    };
+
+                                    writeln!(f, "        {:120}{}", file_line, class_method)?;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    })
+}
+
+pub fn outer_attributes() -> String {
+    ATTRIBUTES.replace("#![", "#[")
+}
+
+/// Names of the methods the preamble adds to a class, which generated methods must not use.
+pub fn reserved_names(class: &str) -> &'static [&'static str] {
+    match class {
+        "java/lang/String" => &["from_env_str", "string_chars", "to_string", "to_string_lossy"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{ACC_PUBLIC, Member, compact, generate, jdk_classes};
+
+    #[test]
+    fn preamble_calls_resolved_names() {
+        let mut classes = jdk_classes();
+        // `get_message` keeps `getMessage` from being renamed to it.
+        let throwable = classes.remove(2);
+        assert_eq!(throwable.name(), "java/lang/Throwable");
+        classes.push(throwable.method(Member::new(ACC_PUBLIC, "get_message", "()V")));
+        let yaml = "rules:\n  - match: \"**\"\n    include: true\n    naming: snake_case\n";
+        let code = generate(yaml, &classes);
+
+        let compact = compact(&code);
+        assert!(compact.contains("matchself.getMessage(){"), "{code}");
+        assert!(compact.contains("matchself.get_localized_message(){"));
+        assert!(compact.contains("frame.get_file_name(),frame.get_line_number()"));
+    }
+}
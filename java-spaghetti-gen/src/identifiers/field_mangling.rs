@@ -1,4 +1,4 @@
-use crate::identifiers::rust_ident;
+use crate::identifiers::{NamingConvention, rust_ident};
 use crate::parser_util::JavaField;
 
 pub enum FieldMangling<'a> {
@@ -6,15 +6,26 @@ pub enum FieldMangling<'a> {
     GetSet(String, String),
 }
 
-pub fn mangle_field<'a>(field: JavaField<'a>) -> Result<FieldMangling<'a>, anyhow::Error> {
-    let field_name = field.name();
+/// Names a field after its Java name following `naming`, or `rename` (see `MemberRule::rename`).
+pub fn mangle_field<'a>(
+    field: JavaField<'a>,
+    rename: Option<&str>,
+    naming: NamingConvention,
+) -> Result<FieldMangling<'a>, anyhow::Error> {
     if let Some(value) = field.constant().as_ref() {
-        let name = rust_ident(field_name)?;
-        Ok(FieldMangling::ConstValue(name, value.clone()))
+        let name = match rename {
+            Some(rename) => rename.to_string(),
+            None => naming.constant(field.name()),
+        };
+        Ok(FieldMangling::ConstValue(rust_ident(&name)?, value.clone()))
     } else {
+        let name = match rename {
+            Some(rename) => rename.to_string(),
+            None => naming.function(field.name()),
+        };
         Ok(FieldMangling::GetSet(
-            rust_ident(field_name)?,
-            rust_ident(&format!("set_{field_name}"))?,
+            rust_ident(&name)?,
+            rust_ident(&format!("set_{name}"))?,
         ))
     }
 }
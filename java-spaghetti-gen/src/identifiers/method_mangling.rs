@@ -2,6 +2,7 @@ use anyhow::bail;
 use cafebabe::descriptors::{FieldType, MethodDescriptor};
 use serde_derive::Deserialize;
 
+use super::naming_convention::NamingConvention;
 use super::rust_identifier::rust_ident;
 use crate::parser_util::{Id, IdPart};
 
@@ -91,16 +92,39 @@ fn method_mangling_style_mangle_test() {
         // TODO: get1DFoo
         // TODO: array types (primitive + non-primitive)
     ] {
-        assert_eq!(MethodManglingStyle::Java.mangle(name, sig).unwrap(), java);
         assert_eq!(
-            MethodManglingStyle::JavaShortSignature.mangle(name, sig).unwrap(),
+            MethodManglingStyle::Java
+                .mangle(name, sig, NamingConvention::Java)
+                .unwrap(),
+            java
+        );
+        assert_eq!(
+            MethodManglingStyle::JavaShortSignature
+                .mangle(name, sig, NamingConvention::Java)
+                .unwrap(),
             java_short
         );
         assert_eq!(
-            MethodManglingStyle::JavaLongSignature.mangle(name, sig).unwrap(),
+            MethodManglingStyle::JavaLongSignature
+                .mangle(name, sig, NamingConvention::Java)
+                .unwrap(),
             java_long
         );
     }
+
+    let snake = NamingConvention::SnakeCase;
+    assert_eq!(
+        MethodManglingStyle::JavaShortSignature
+            .mangle("getFoo", &desc_arg_obj_ret_v, snake)
+            .unwrap(),
+        "get_foo_object"
+    );
+    assert_eq!(
+        MethodManglingStyle::JavaLongSignature
+            .mangle("<init>", &desc_arg_obj_ret_v, snake)
+            .unwrap(),
+        "new_java_lang_object"
+    );
 }
 
 #[test]
@@ -112,19 +136,44 @@ fn mangle_method_name_test() {
         return_type: ReturnDescriptor::Void,
     };
 
-    assert_eq!(MethodManglingStyle::Java.mangle("isFooBar", &desc).unwrap(), "isFooBar");
     assert_eq!(
-        MethodManglingStyle::Java.mangle("XMLHttpRequest", &desc).unwrap(),
+        MethodManglingStyle::Java
+            .mangle("isFooBar", &desc, NamingConvention::Java)
+            .unwrap(),
+        "isFooBar"
+    );
+    assert_eq!(
+        MethodManglingStyle::Java
+            .mangle("XMLHttpRequest", &desc, NamingConvention::Java)
+            .unwrap(),
         "XMLHttpRequest"
     );
     assert_eq!(
-        MethodManglingStyle::Java.mangle("getFieldID_Input", &desc).unwrap(),
+        MethodManglingStyle::Java
+            .mangle("getFieldID_Input", &desc, NamingConvention::Java)
+            .unwrap(),
         "getFieldID_Input"
     );
+
+    let snake = NamingConvention::SnakeCase;
+    assert_eq!(
+        MethodManglingStyle::Java.mangle("isFooBar", &desc, snake).unwrap(),
+        "is_foo_bar"
+    );
+    assert_eq!(MethodManglingStyle::Java.mangle("<init>", &desc, snake).unwrap(), "new");
+    assert_eq!(
+        MethodManglingStyle::Java.mangle("Type", &desc, snake).unwrap(),
+        "r#type"
+    );
 }
 
 impl MethodManglingStyle {
-    pub fn mangle(&self, name: &str, descriptor: &MethodDescriptor) -> Result<String, anyhow::Error> {
+    pub fn mangle(
+        &self,
+        name: &str,
+        descriptor: &MethodDescriptor,
+        naming: NamingConvention,
+    ) -> Result<String, anyhow::Error> {
         let name = match name {
             "" => {
                 bail!("empty string")
@@ -136,13 +185,19 @@ impl MethodManglingStyle {
             name => name,
         };
 
+        rust_ident(&naming.function(&format!("{name}{}", self.suffix(descriptor))))
+    }
+
+    /// Returns the type names appended to method names for disambiguation, e.g. `_int_Object`, or an empty string
+    /// for [Self::Java].
+    pub fn suffix(&self, descriptor: &MethodDescriptor) -> String {
         let long_sig = match self {
-            MethodManglingStyle::Java => return rust_ident(name),
+            MethodManglingStyle::Java => return String::new(),
             MethodManglingStyle::JavaShortSignature => false,
             MethodManglingStyle::JavaLongSignature => true,
         };
 
-        let mut buffer = name.to_string();
+        let mut buffer = String::new();
 
         for arg in descriptor.parameters.iter() {
             match &arg.field_type {
@@ -190,6 +245,6 @@ impl MethodManglingStyle {
             }
         }
 
-        rust_ident(&buffer)
+        buffer
     }
 }
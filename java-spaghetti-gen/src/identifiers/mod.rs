@@ -2,8 +2,12 @@
 
 mod field_mangling;
 mod method_mangling;
+mod naming_convention;
+mod parameter_names;
 mod rust_identifier;
 
 pub use field_mangling::*;
 pub use method_mangling::*;
+pub use naming_convention::*;
+pub use parameter_names::*;
 pub use rust_identifier::*;
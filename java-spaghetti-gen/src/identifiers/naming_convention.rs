@@ -0,0 +1,107 @@
+use serde_derive::Deserialize;
+
+/// How Java method and field names are turned into Rust names, before they are escaped with [super::rust_ident].
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NamingConvention {
+    /// Keep the Java names.
+    ///
+    /// | Java             | Rust             |
+    /// | ---------------- | ---------------- |
+    /// | getFoo           | getFoo           |
+    /// | foo (field)      | foo, set_foo     |
+    /// | MAX_VALUE        | MAX_VALUE        |
+    #[default]
+    Java,
+
+    /// Name methods and field accessors in `snake_case` and constants in `SCREAMING_SNAKE_CASE`. Methods that
+    /// still collide after being disambiguated by their signatures keep their Java names.
+    ///
+    /// | Java             | Rust             |
+    /// | ---------------- | ---------------- |
+    /// | getFoo           | get_foo          |
+    /// | getURLForName    | get_url_for_name |
+    /// | fooBar (field)   | foo_bar, set_foo_bar |
+    /// | serialVersionUID | SERIAL_VERSION_UID |
+    /// | TIMED_WAITING (enum constant) | TimedWaiting |
+    SnakeCase,
+}
+
+impl NamingConvention {
+    /// Names a method or field accessor.
+    pub fn function(&self, name: &str) -> String {
+        match self {
+            Self::Java => name.to_string(),
+            Self::SnakeCase => snake_case(name),
+        }
+    }
+
+    /// Names a constant.
+    pub fn constant(&self, name: &str) -> String {
+        match self {
+            Self::Java => name.to_string(),
+            Self::SnakeCase => snake_case(name).to_uppercase(),
+        }
+    }
+
+    /// Names a variant of the Rust enum generated for a Java enum.
+    pub fn variant(&self, name: &str) -> String {
+        match self {
+            Self::Java => name.to_string(),
+            Self::SnakeCase => snake_case(name)
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                        .unwrap_or_default()
+                })
+                .collect::<String>(),
+        }
+    }
+}
+
+/// Converts a camelCase name to snake_case. Acronyms are kept in one word, digits stay with the preceding word.
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            let boundary = prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower);
+            if boundary && !out.ends_with('_') {
+                out.push('_');
+            }
+        }
+        out.extend(ch.to_lowercase());
+    }
+    out
+}
+
+#[test]
+fn snake_case_test() {
+    assert_eq!(snake_case("getFoo"), "get_foo");
+    assert_eq!(snake_case("XMLHttpRequest"), "xml_http_request");
+    assert_eq!(snake_case("getURLForName"), "get_url_for_name");
+    assert_eq!(snake_case("getFieldID_Input"), "get_field_id_input");
+    assert_eq!(snake_case("getFoo_java_lang_Object"), "get_foo_java_lang_object");
+    assert_eq!(snake_case("get1DFoo"), "get1_d_foo");
+    assert_eq!(snake_case("toUTF8"), "to_utf8");
+    assert_eq!(snake_case("MAX_VALUE"), "max_value");
+    assert_eq!(snake_case("_private"), "_private");
+    assert_eq!(snake_case("new"), "new");
+    assert_eq!(snake_case("baseURLString"), "base_url_string");
+    assert_eq!(snake_case("x2Offset"), "x2_offset");
+
+    assert_eq!(
+        NamingConvention::SnakeCase.constant("serialVersionUID"),
+        "SERIAL_VERSION_UID"
+    );
+    assert_eq!(NamingConvention::SnakeCase.constant("MAX_VALUE"), "MAX_VALUE");
+    assert_eq!(NamingConvention::Java.function("getFoo"), "getFoo");
+    assert_eq!(NamingConvention::SnakeCase.variant("TIMED_WAITING"), "TimedWaiting");
+    assert_eq!(NamingConvention::SnakeCase.variant("Dark"), "Dark");
+    assert_eq!(NamingConvention::Java.variant("TIMED_WAITING"), "TIMED_WAITING");
+}
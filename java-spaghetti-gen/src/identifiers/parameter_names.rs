@@ -0,0 +1,117 @@
+use super::{rust_ident, snake_case};
+
+/// Names of Rust parameters and locals in generated methods and proxies that parameter names must not shadow.
+const RUST_RESERVED: &[&str] = &["env", "ptr", "_class"];
+
+/// Names that parameters of generated Java proxy methods must not shadow.
+const JAVA_RESERVED: &[&str] = &["ptr"];
+
+const JAVA_KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+    "_",
+];
+
+/// Returns Rust names for `count` method parameters: the snake_cased and keyword-escaped `java_names` where
+/// available and valid, `argN` otherwise.
+pub fn rust_param_names(java_names: Option<&[String]>, count: usize) -> Vec<String> {
+    unique_names(count, RUST_RESERVED, |i| {
+        let name = rust_ident(&snake_case(java_names?.get(i)?)).ok()?;
+        (!name.starts_with("__jni")).then_some(name)
+    })
+}
+
+/// Returns Java names for `count` method parameters: `java_names` where available and valid, `argN` otherwise.
+pub fn java_param_names(java_names: Option<&[String]>, count: usize) -> Vec<String> {
+    unique_names(count, JAVA_RESERVED, |i| {
+        let name = java_names?.get(i)?;
+        let valid = name
+            .chars()
+            .enumerate()
+            .all(|(i, ch)| ch.is_ascii_alphabetic() || ch == '_' || ch == '$' || (i > 0 && ch.is_ascii_digit()));
+        (valid && !name.is_empty() && !JAVA_KEYWORDS.contains(&name.as_str())).then(|| name.clone())
+    })
+}
+
+fn unique_names(count: usize, reserved: &[&str], name: impl Fn(usize) -> Option<String>) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut name = name(i).unwrap_or_else(|| format!("arg{i}"));
+        while reserved.contains(&name.as_str()) || names.contains(&name) {
+            name.push('_');
+        }
+        names.push(name);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn param_names_test() {
+        let names = ["type", "fromIndex", "env", "this$0", "fromIndex"].map(String::from);
+        assert_eq!(
+            rust_param_names(Some(&names), 5),
+            ["r#type", "from_index", "env_", "arg3", "from_index_"]
+        );
+        assert_eq!(
+            java_param_names(Some(&names), 5),
+            ["type", "fromIndex", "env", "this$0", "fromIndex_"]
+        );
+        assert_eq!(rust_param_names(None, 2), ["arg0", "arg1"]);
+        assert_eq!(java_param_names(Some(&["ptr".to_string()]), 2), ["ptr_", "arg1"]);
+    }
+}
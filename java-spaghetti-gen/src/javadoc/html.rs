@@ -0,0 +1,250 @@
+//! Reads the descriptions from the class pages of javadoc HTML.
+//!
+//! Members are found by their anchors, which are `id` or `name` attributes such as `put(java.lang.Object,int[])`
+//! or `<init>(java.lang.String)` since JDK 9, `put-java.lang.Object-int:A-` in JDK 8, or the name of a field.
+//! The description is the first `<div class="block">` after the anchor.
+
+use super::text::{self, Resolve, Tag, decode_entities};
+use super::{ClassDocs, MethodDoc};
+
+/// Returns the documentation of a class page. `page` is the path of the page relative to the documentation root,
+/// and `class` the JNI path of the class.
+pub(super) fn parse(html: &str, page: &str, class: &str) -> Vec<(String, ClassDocs)> {
+    let resolver = Resolver { page };
+    let simple_class = class.rsplit(['/', '$']).next().unwrap_or(class);
+    let mut docs = ClassDocs::default();
+
+    // (anchor, position of the tag)
+    let mut anchors: Vec<(String, usize)> = Vec::new();
+    let mut blocks: Vec<(usize, &str)> = Vec::new();
+    let mut pos = 0;
+    while let Some(offset) = html[pos..].find('<') {
+        pos += offset;
+        let Some(tag) = Tag::parse(&html[pos..]) else {
+            pos += 1;
+            continue;
+        };
+        let start = pos;
+        pos += tag.len;
+        if tag.closing {
+            continue;
+        }
+        // `name` is also an attribute of `<meta>` and `<input>`.
+        let name = || (tag.name == "a").then(|| tag.attribute("name")).flatten();
+        if let Some(anchor) = tag.attribute("id").or_else(name) {
+            anchors.push((anchor, start));
+        }
+        if tag.name == "div" && tag.attribute("class").as_deref() == Some("block") {
+            let content = div_content(&html[pos..]);
+            blocks.push((start, content));
+            pos += content.len();
+        }
+    }
+
+    // The class description comes before the summaries of the members.
+    let first_member = anchors
+        .iter()
+        .find(|(anchor, _)| anchor.contains("summary") || member(anchor, simple_class).is_some())
+        .map_or(html.len(), |&(_, pos)| pos);
+    docs.doc = blocks
+        .iter()
+        .find(|&&(pos, _)| pos < first_member)
+        .and_then(|(_, content)| text::summary(content, &resolver));
+
+    for (i, (anchor, start)) in anchors.iter().enumerate() {
+        let Some(member) = member(anchor, simple_class) else {
+            continue;
+        };
+        let end = anchors.get(i + 1).map_or(html.len(), |&(_, pos)| pos);
+        let Some((_, content)) = blocks.iter().find(|&&(pos, _)| pos > *start && pos < end) else {
+            continue;
+        };
+        let Some(doc) = text::summary(content, &resolver) else {
+            continue;
+        };
+        match member {
+            Member::Method(name, params) => docs.methods.push(MethodDoc { name, params, doc }),
+            Member::Field(name) => {
+                docs.fields.insert(name, doc);
+            }
+        }
+    }
+    vec![(class.to_string(), docs)]
+}
+
+/// Returns the content of a `<div>` up to its end tag, given the input after the start tag.
+fn div_content(html: &str) -> &str {
+    let mut depth = 0;
+    let mut pos = 0;
+    while let Some(offset) = html[pos..].find('<') {
+        pos += offset;
+        let Some(tag) = Tag::parse(&html[pos..]) else {
+            pos += 1;
+            continue;
+        };
+        if tag.name == "div" {
+            match tag.closing {
+                true if depth == 0 => return &html[..pos],
+                true => depth -= 1,
+                false => depth += 1,
+            }
+        }
+        pos += tag.len;
+    }
+    html
+}
+
+enum Member {
+    /// The name, `<init>` for constructors, and the simple names of the parameter types.
+    Method(String, Vec<String>),
+    Field(String),
+}
+
+fn member(anchor: &str, simple_class: &str) -> Option<Member> {
+    let is_ident = |s: &str| {
+        s.starts_with(|ch: char| ch.is_alphabetic() || ch == '_' || ch == '$')
+            && s.chars().all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$')
+    };
+    let method = |name: &str, params: Vec<String>| {
+        let name = match name == simple_class {
+            true => "<init>",
+            false => name,
+        };
+        Some(Member::Method(name.to_string(), params))
+    };
+
+    if let Some((name, params)) = anchor.split_once('(')
+        && let Some(params) = params.strip_suffix(')')
+        && (is_ident(name) || name == "<init>")
+    {
+        let params = params
+            .split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            .map(|param| simple_type_name(param.replace("...", "[]").as_str()))
+            .collect();
+        return method(name, params);
+    }
+    if let Some((name, params)) = anchor.split_once('-')
+        && let Some(params) = params.strip_suffix('-')
+        && is_ident(name)
+    {
+        let params = params
+            .split('-')
+            .filter(|param| !param.is_empty())
+            .map(|param| simple_type_name(param.replace(":A", "[]").replace("...", "[]").as_str()))
+            .collect();
+        return method(name, params);
+    }
+    is_ident(anchor).then(|| Member::Field(anchor.to_string()))
+}
+
+/// `java.util.Map.Entry[]` => `Entry[]`
+fn simple_type_name(name: &str) -> String {
+    let dimensions = name.find('[').unwrap_or(name.len());
+    let (name, brackets) = name.split_at(dimensions);
+    let name = name.rsplit('.').next().unwrap_or(name);
+    format!("{name}{brackets}")
+}
+
+struct Resolver<'a> {
+    /// Path of the page relative to the documentation root.
+    page: &'a str,
+}
+
+impl Resolve for Resolver<'_> {
+    /// Class names in `<code>` are not resolved, links are already `<a href>` elements.
+    fn class(&self, _name: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Resolves the page a relative link points to, returning the classes it may document. Pages of other modules
+    /// of the JDK have the module name as the first directory.
+    fn href(&self, href: &str) -> Vec<String> {
+        let href = decode_entities(href);
+        let page = href.split(['#', '?']).next().unwrap_or_default();
+        let Some(page) = page.strip_suffix(".html") else {
+            return Vec::new();
+        };
+        if page.contains(':') {
+            return Vec::new();
+        }
+        let mut segments: Vec<&str> = self.page.split('/').collect();
+        segments.pop();
+        for segment in page.split('/') {
+            match segment {
+                "." | "" => {}
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+        let Some((name, packages)) = segments.split_last() else {
+            return Vec::new();
+        };
+        let name = name.replace('.', "$");
+        (0..=packages.len())
+            .map(|skip| {
+                let mut path: Vec<&str> = packages[skip..].to_vec();
+                path.push(&name);
+                path.join("/")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        // Abbreviated from the output of javadoc 17 and 8.
+        let html = r#"<html><head><meta name="description" content="declaration"></head><body>
+            <nav id="navbar-top"></nav>
+            <section class="class-description" id="class-description">
+            <div class="block">A person, see <a href="../util/Names.html" title="class"><code>Names</code></a>.</div>
+            </section>
+            <section class="method-summary" id="method-summary">
+            <div class="block">Greets.</div>
+            </section>
+            <section class="detail" id="&lt;init&gt;(java.lang.String,int[])"><h3>Person</h3>
+            <div class="block">Creates a person.<div class="other">x</div> More.</div></section>
+            <section class="detail" id="greet(com.example.Person)"><h3>greet</h3>
+            <div class="block">Greets <code>other</code>.</div></section>
+            <section class="detail" id="noDocs()"><h3>noDocs</h3></section>
+            <a name="setNames-java.util.List-java.lang.String...-"><!-- --></a>
+            <div class="block">Sets the names.</div>
+            <a name="NAME"><!-- --></a><ul><li><div class="block">The name.</div></li></ul>
+        </body></html>"#;
+        let classes = parse(html, "com/example/Person.html", "com/example/Person");
+        let (_, docs) = &classes[0];
+        assert_eq!(
+            docs.doc.as_ref().unwrap().0[1],
+            super::super::DocPart::Link {
+                classes: vec![
+                    "com/util/Names".to_string(),
+                    "util/Names".to_string(),
+                    "Names".to_string()
+                ],
+                text: "Names".to_string(),
+                code: true,
+            }
+        );
+        let methods: Vec<_> = docs
+            .methods
+            .iter()
+            .map(|m| (m.name.as_str(), m.params.clone()))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                ("<init>", vec!["String".to_string(), "int[]".to_string()]),
+                ("greet", vec!["Person".to_string()]),
+                ("setNames", vec!["List".to_string(), "String[]".to_string()]),
+            ]
+        );
+        assert!(docs.fields.contains_key("NAME"));
+    }
+}
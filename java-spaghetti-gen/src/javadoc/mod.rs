@@ -0,0 +1,270 @@
+//! Documentation of classes, methods and fields, read from `-sources.jar` files or javadoc HTML (see `Config::sources`).
+
+mod html;
+mod source;
+mod text;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use anyhow::Context as _;
+use cafebabe::descriptors::{FieldDescriptor, FieldType, MethodDescriptor};
+
+use crate::emit::Context;
+use crate::parser_util::Id;
+
+/// Documentation of the generated classes, by JNI path.
+#[derive(Debug, Default)]
+pub struct Javadoc {
+    classes: HashMap<String, ClassDocs>,
+}
+
+#[derive(Debug, Default)]
+struct ClassDocs {
+    doc: Option<Doc>,
+    methods: Vec<MethodDoc>,
+    fields: HashMap<String, Doc>,
+}
+
+#[derive(Debug)]
+struct MethodDoc {
+    /// `<init>` for constructors.
+    name: String,
+    /// Simple names of the parameter types as written in the documentation, e.g. `String`, `int[]` or `T`.
+    params: Vec<String>,
+    doc: Doc,
+}
+
+/// The first paragraph of a description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Doc(Vec<DocPart>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum DocPart {
+    Text(String),
+    Code(String),
+    /// A reference to a class, linked if it is generated.
+    Link {
+        /// Candidate JNI paths of the class, the first generated one is used.
+        classes: Vec<String>,
+        text: String,
+        code: bool,
+    },
+    Url {
+        url: String,
+        text: String,
+    },
+}
+
+impl Javadoc {
+    /// Reads the documentation of generated classes from a `.jar` or `.zip` archive, or a directory, of Java sources
+    /// or javadoc HTML pages.
+    pub fn read(&mut self, path: &Path, context: &Context) -> anyhow::Result<()> {
+        if path.is_dir() {
+            return self.read_dir(path, path, context);
+        }
+        let reading = || format!("reading sources {}", path.display());
+        let file = File::open(path).with_context(reading)?;
+        let mut archive = zip::ZipArchive::new(io::BufReader::new(file)).with_context(reading)?;
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).with_context(reading)?;
+            if !file.is_file() {
+                continue;
+            }
+            let name = file.name().to_string();
+            if let Some(page) = self.page(&name, context) {
+                let mut buf = Vec::new();
+                file.read_to_end(&mut buf)
+                    .with_context(|| format!("reading sources {}: {name}", path.display()))?;
+                self.add_page(&name, page, &String::from_utf8_lossy(&buf));
+            }
+        }
+        Ok(())
+    }
+
+    fn read_dir(&mut self, root: &Path, dir: &Path, context: &Context) -> anyhow::Result<()> {
+        let mut entries = std::fs::read_dir(dir)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|e| e.path()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .with_context(|| format!("reading sources {}", dir.display()))?;
+        entries.sort();
+
+        for entry in entries {
+            if entry.is_dir() {
+                self.read_dir(root, &entry, context)?;
+                continue;
+            }
+            let name = entry.strip_prefix(root)?.to_string_lossy().replace('\\', "/");
+            if let Some(page) = self.page(&name, context) {
+                let buf = std::fs::read(&entry).with_context(|| format!("reading sources {}", entry.display()))?;
+                self.add_page(&name, page, &String::from_utf8_lossy(&buf));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the generated class documented by a file, skipping over leading directories such as `src/main/java/`
+    /// or the module directories of the JDK's `src.zip`.
+    fn page(&self, name: &str, context: &Context) -> Option<Page> {
+        let (stem, html) = match (name.strip_suffix(".java"), name.strip_suffix(".html")) {
+            (Some(stem), _) => (stem, false),
+            (_, Some(stem)) => (stem, true),
+            _ => return None,
+        };
+        let mut prefix = 0;
+        loop {
+            let path = &stem[prefix..];
+            let class = match html {
+                false => path.to_string(),
+                true => match path.rsplit_once('/') {
+                    Some((package, name)) => format!("{package}/{}", name.replace('.', "$")),
+                    None => path.replace('.', "$"),
+                },
+            };
+            if context.all_classes.contains_key(&class) {
+                return (!self.classes.contains_key(&class)).then_some(Page { prefix, class, html });
+            }
+            prefix += path.find('/')? + 1;
+        }
+    }
+
+    fn add_page(&mut self, name: &str, page: Page, content: &str) {
+        let classes = match page.html {
+            false => source::parse(content, &page.class),
+            true => html::parse(content, &name[page.prefix..], &page.class),
+        };
+        for (class, docs) in classes {
+            self.classes.entry(class).or_insert(docs);
+        }
+    }
+
+    pub fn class(&self, class: &str) -> Option<&Doc> {
+        self.classes.get(class)?.doc.as_ref()
+    }
+
+    /// Finds the documentation of a method by the simple names of its parameter types. Type variables in the
+    /// documentation match any type, and constructors may have synthetic parameters first, like the outer instance.
+    pub fn method(&self, class: &str, name: &str, descriptor: &MethodDescriptor) -> Option<&Doc> {
+        let params: Vec<String> = descriptor.parameters.iter().map(simple_type_name).collect();
+        let candidates: Vec<&MethodDoc> = self
+            .classes
+            .get(class)?
+            .methods
+            .iter()
+            .filter(|m| {
+                m.name == name
+                    && (m.params.len() == params.len() || (name == "<init>" && m.params.len() < params.len()))
+            })
+            .collect();
+        let matching: Vec<&MethodDoc> = candidates
+            .iter()
+            .copied()
+            .filter(|m| {
+                let params = &params[params.len() - m.params.len()..];
+                m.params
+                    .iter()
+                    .zip(params)
+                    .all(|(doc, param)| doc == param || is_type_variable(doc))
+            })
+            .collect();
+        match (&matching[..], &candidates[..]) {
+            ([method], _) => Some(&method.doc),
+            ([], [method]) if method.params.len() == params.len() => Some(&method.doc),
+            _ => None,
+        }
+    }
+
+    pub fn field(&self, class: &str, name: &str) -> Option<&Doc> {
+        self.classes.get(class)?.fields.get(name)
+    }
+}
+
+struct Page {
+    /// Length of the leading directories in the file name.
+    prefix: usize,
+    class: String,
+    html: bool,
+}
+
+impl Doc {
+    /// Renders the documentation as Markdown for the Rust module `mod_`, linking to generated public classes.
+    pub fn render(&self, context: &Context, mod_: &str) -> String {
+        let mut out = String::new();
+        for part in &self.0 {
+            match part {
+                DocPart::Text(text) => out.push_str(&escape(text)),
+                DocPart::Code(code) => out.push_str(&code_span(code)),
+                DocPart::Link { classes, text, code } => {
+                    let label = match code {
+                        true => code_span(text),
+                        false => escape(text),
+                    };
+                    match classes.iter().find_map(|class| link_path(context, class, mod_)) {
+                        Some(path) => out.push_str(&format!("[{label}]({path})")),
+                        None => out.push_str(&label),
+                    }
+                }
+                DocPart::Url { url, text } => out.push_str(&format!("[{}](<{url}>)", escape(text))),
+            }
+        }
+        out
+    }
+}
+
+fn link_path(context: &Context, class: &str, mod_: &str) -> Option<String> {
+    let generated = context.all_classes.get(class)?;
+    if !generated.java.is_public() && !context.config.resolve_class(class).include_private_classes {
+        return None;
+    }
+    let path = context.java_to_rust_path(Id(class), mod_).ok()?;
+    Some(path.to_string().replace(' ', ""))
+}
+
+/// Escapes characters with a meaning in Markdown or HTML.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+fn code_span(code: &str) -> String {
+    match code.contains('`') {
+        false => format!("`{code}`"),
+        true => format!("`` {code} ``"),
+    }
+}
+
+fn simple_type_name(descriptor: &FieldDescriptor) -> String {
+    let name = match &descriptor.field_type {
+        FieldType::Byte => "byte",
+        FieldType::Char => "char",
+        FieldType::Double => "double",
+        FieldType::Float => "float",
+        FieldType::Integer => "int",
+        FieldType::Long => "long",
+        FieldType::Short => "short",
+        FieldType::Boolean => "boolean",
+        FieldType::Object(class) => {
+            let class = Id::from(class).as_str();
+            class.rsplit(['/', '$']).next().unwrap_or(class)
+        }
+    };
+    format!("{name}{}", "[]".repeat(descriptor.dimensions as usize))
+}
+
+/// Type variables are conventionally named with a single capital letter, possibly followed by a digit.
+fn is_type_variable(name: &str) -> bool {
+    let name = name.trim_end_matches("[]");
+    let mut chars = name.chars();
+    chars.next().is_some_and(|ch| ch.is_ascii_uppercase()) && chars.all(|ch| ch.is_ascii_digit())
+}
@@ -0,0 +1,624 @@
+//! Reads documentation comments from Java sources, matching them with the declarations that follow.
+
+use std::collections::HashMap;
+
+use super::text::{self, Resolve};
+use super::{ClassDocs, MethodDoc};
+
+const CLASS_KEYWORDS: &[&str] = &["class", "interface", "enum", "record"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Punct(char),
+    /// A string, character or number literal.
+    Literal,
+    /// The text of a `/** ... */` comment.
+    Doc(&'a str),
+}
+
+/// Returns the documentation of the classes declared in a source file, including nested classes. `class` is the
+/// JNI path of the top-level class, which the package is taken from.
+pub(super) fn parse(source: &str, class: &str) -> Vec<(String, ClassDocs)> {
+    let mut parser = Parser {
+        tokens: tokenize(source),
+        pos: 0,
+        package: class.rsplit_once('/').map_or("", |(package, _)| package).to_string(),
+        imports: HashMap::new(),
+        wildcard_imports: Vec::new(),
+        classes: Vec::new(),
+    };
+    parser.header();
+    parser.body(&[], false);
+    parser.classes
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+    /// JNI path of the package.
+    package: String,
+    /// Imported classes by simple name, as JNI paths.
+    imports: HashMap<String, String>,
+    /// Packages imported with `.*`, as JNI paths.
+    wildcard_imports: Vec<String>,
+    classes: Vec<(String, ClassDocs)>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<Token<'a>> {
+        self.tokens.get(self.pos + offset).copied()
+    }
+
+    /// Reads the package and import declarations.
+    fn header(&mut self) {
+        loop {
+            match self.peek() {
+                // License headers may be documentation comments.
+                Some(Token::Doc(_)) if matches!(self.peek_at(1), Some(Token::Ident("package" | "import"))) => {
+                    self.pos += 1
+                }
+                Some(Token::Ident("package")) => {
+                    self.pos += 1;
+                    self.package = self.qualified_name().replace('.', "/");
+                    self.pos += 1; // ;
+                }
+                Some(Token::Ident("import")) => {
+                    self.pos += 1;
+                    let is_static = self.peek() == Some(Token::Ident("static"));
+                    if is_static {
+                        self.pos += 1;
+                    }
+                    let name = self.qualified_name();
+                    if !is_static {
+                        match name.strip_suffix(".*") {
+                            Some(package) => self.wildcard_imports.push(package.replace('.', "/")),
+                            None => {
+                                let simple = name.rsplit('.').next().unwrap_or(&name).to_string();
+                                self.imports.insert(simple, name.replace('.', "/"));
+                            }
+                        }
+                    }
+                    self.pos += 1; // ;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Reads a dotted name, possibly ending with `.*`.
+    fn qualified_name(&mut self) -> String {
+        let mut name = String::new();
+        loop {
+            match self.peek() {
+                Some(Token::Ident(ident)) => name.push_str(ident),
+                Some(Token::Punct('*')) => name.push('*'),
+                _ => break,
+            }
+            self.pos += 1;
+            if self.peek() != Some(Token::Punct('.')) {
+                break;
+            }
+            name.push('.');
+            self.pos += 1;
+        }
+        name
+    }
+
+    /// Skips an annotation such as `@Deprecated(since = "9")`.
+    fn annotation(&mut self) {
+        self.pos += 1;
+        self.qualified_name();
+        if self.peek() == Some(Token::Punct('(')) {
+            self.skip_balanced();
+        }
+    }
+
+    /// Skips a balanced `(...)`, `[...]` or `{...}` group starting at the current token.
+    fn skip_balanced(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            self.pos += 1;
+            match token {
+                Token::Punct('(' | '[' | '{') => depth += 1,
+                Token::Punct(')' | ']' | '}') => {
+                    depth -= 1;
+                    if depth <= 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the members of a class body up to the closing brace, or the declarations of a file. `outer` are the JNI
+    /// paths of the enclosing classes, innermost last.
+    fn body(&mut self, outer: &[String], is_enum: bool) {
+        let mut doc = None;
+        if is_enum {
+            self.enum_constants(outer);
+        }
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Doc(text) => {
+                    doc = Some(text);
+                    self.pos += 1;
+                }
+                Token::Punct(';') => self.pos += 1,
+                Token::Punct('}') => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => self.member(outer, doc.take()),
+            }
+        }
+    }
+
+    fn enum_constants(&mut self, outer: &[String]) {
+        let mut doc = None;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Doc(text) => {
+                    doc = Some(text);
+                    self.pos += 1;
+                }
+                Token::Punct('@') => self.annotation(),
+                Token::Ident(name) => {
+                    self.pos += 1;
+                    if self.peek() == Some(Token::Punct('(')) {
+                        self.skip_balanced();
+                    }
+                    if self.peek() == Some(Token::Punct('{')) {
+                        self.skip_balanced();
+                    }
+                    if let Some(doc) = doc.take().and_then(|doc| self.doc(doc, outer)) {
+                        self.class_docs(outer).fields.insert(name.to_string(), doc);
+                    }
+                    if self.peek() == Some(Token::Punct(',')) {
+                        self.pos += 1;
+                    }
+                }
+                Token::Punct(';') => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Reads a declaration of a class, method or field, or an initializer block.
+    fn member(&mut self, outer: &[String], doc: Option<&'a str>) {
+        let mut header = Vec::new();
+        let mut depth = 0;
+        let mut initializer = false;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct('@') if self.peek_at(1) != Some(Token::Ident("interface")) => {
+                    self.annotation();
+                    continue;
+                }
+                Token::Punct('(' | '[') => depth += 1,
+                Token::Punct(')' | ']') => depth -= 1,
+                Token::Punct('=') if depth == 0 => initializer = true,
+                Token::Punct('{') if depth == 0 && initializer => {
+                    self.skip_balanced();
+                    continue;
+                }
+                Token::Punct('{') if depth == 0 => {
+                    let keyword = header
+                        .iter()
+                        .position(|token| matches!(token, Token::Ident(ident) if CLASS_KEYWORDS.contains(ident)));
+                    if let Some(keyword) = keyword
+                        && let Some(Token::Ident(name)) = header.get(keyword + 1)
+                    {
+                        self.pos += 1;
+                        let path = match outer.last() {
+                            Some(outer) => format!("{outer}${name}"),
+                            None if self.package.is_empty() => name.to_string(),
+                            None => format!("{}/{name}", self.package),
+                        };
+                        let mut outer = outer.to_vec();
+                        outer.push(path);
+                        let doc = doc.and_then(|doc| self.doc(doc, &outer));
+                        self.class_docs(&outer).doc = doc;
+                        self.body(&outer, header[keyword] == Token::Ident("enum"));
+                        return;
+                    }
+                    self.skip_balanced();
+                    if header.contains(&Token::Punct('(')) {
+                        self.method(outer, doc, &header);
+                    }
+                    return;
+                }
+                Token::Punct(';') if depth == 0 => {
+                    self.pos += 1;
+                    match header.contains(&Token::Punct('(')) && !initializer {
+                        true => self.method(outer, doc, &header),
+                        false => self.fields(outer, doc, &header),
+                    }
+                    return;
+                }
+                Token::Punct('}') if depth == 0 => return,
+                Token::Doc(_) => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            header.push(token);
+            self.pos += 1;
+        }
+    }
+
+    fn method(&mut self, outer: &[String], doc: Option<&'a str>, header: &[Token<'a>]) {
+        let (Some(doc), Some(class)) = (doc, outer.last()) else {
+            return;
+        };
+        let Some(open) = header.iter().position(|token| *token == Token::Punct('(')) else {
+            return;
+        };
+        let Some(Token::Ident(name)) = open.checked_sub(1).and_then(|i| header.get(i)) else {
+            return;
+        };
+        let simple_class = class.rsplit(['/', '$']).next().unwrap_or(class);
+        let name = match *name == simple_class {
+            true => "<init>".to_string(),
+            false => name.to_string(),
+        };
+
+        let mut params = Vec::new();
+        let mut param = Vec::new();
+        let (mut parens, mut angles) = (0, 0);
+        for &token in &header[open + 1..] {
+            match token {
+                Token::Punct('(') => parens += 1,
+                Token::Punct(')') if parens == 0 => break,
+                Token::Punct(')') => parens -= 1,
+                Token::Punct('<') => angles += 1,
+                Token::Punct('>') => angles -= 1,
+                Token::Punct(',') if parens == 0 && angles == 0 => {
+                    params.push(simple_param_type(&param));
+                    param.clear();
+                    continue;
+                }
+                _ => {}
+            }
+            param.push(token);
+        }
+        if !param.is_empty() {
+            params.push(simple_param_type(&param));
+        }
+
+        if let Some(doc) = self.doc(doc, outer) {
+            self.class_docs(outer).methods.push(MethodDoc { name, params, doc });
+        }
+    }
+
+    fn fields(&mut self, outer: &[String], doc: Option<&'a str>, header: &[Token<'a>]) {
+        let Some(doc) = doc.and_then(|doc| self.doc(doc, outer)) else {
+            return;
+        };
+        // Declarators are separated by commas outside of type arguments and initializers.
+        let mut names = Vec::new();
+        let (mut depth, mut angles) = (0, 0);
+        let mut initializer = false;
+        let mut last_ident = None;
+        for &token in header {
+            match token {
+                Token::Ident(ident) if !initializer => last_ident = Some(ident),
+                Token::Punct('(' | '[' | '{') => depth += 1,
+                Token::Punct(')' | ']' | '}') => depth -= 1,
+                Token::Punct('<') if !initializer => angles += 1,
+                Token::Punct('>') if !initializer => angles -= 1,
+                Token::Punct('=') if depth == 0 => {
+                    names.extend(last_ident.take());
+                    initializer = true;
+                }
+                Token::Punct(',') if depth == 0 && angles == 0 => {
+                    names.extend(last_ident.take());
+                    initializer = false;
+                }
+                _ => {}
+            }
+        }
+        names.extend(last_ident);
+        let docs = self.class_docs(outer);
+        for name in names {
+            docs.fields.insert(name.to_string(), doc.clone());
+        }
+    }
+
+    fn class_docs(&mut self, outer: &[String]) -> &mut ClassDocs {
+        let class = outer.last().unwrap();
+        let index = match self.classes.iter().position(|(path, _)| path == class) {
+            Some(index) => index,
+            None => {
+                self.classes.push((class.clone(), ClassDocs::default()));
+                self.classes.len() - 1
+            }
+        };
+        &mut self.classes[index].1
+    }
+
+    fn doc(&self, comment: &str, outer: &[String]) -> Option<super::Doc> {
+        let resolver = Resolver { parser: self, outer };
+        text::summary(&description(comment), &resolver)
+    }
+}
+
+/// Returns the simple name of a parameter type, e.g. `String[]` for `final java.lang.String... args`.
+fn simple_param_type(tokens: &[Token]) -> String {
+    let mut angles = 0;
+    let mut idents = Vec::new();
+    let mut dimensions = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Punct('<') => angles += 1,
+            Token::Punct('>') => angles -= 1,
+            Token::Ident(ident) if angles == 0 && ident != "final" => idents.push(ident),
+            Token::Punct('[') if angles == 0 => dimensions += 1,
+            Token::Punct('.') if angles == 0 && tokens[i..].starts_with(&[Token::Punct('.'); 3]) => {
+                dimensions += 1;
+                i += 2;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    // The last identifier is the parameter name, the one before it the simple name of the type.
+    let name = match idents.len() {
+        0 => "",
+        1 => idents[0],
+        n => idents[n - 2],
+    };
+    format!("{name}{}", "[]".repeat(dimensions))
+}
+
+/// Returns the main description of a documentation comment: the lines before the first block tag such as
+/// `@param`, without leading asterisks.
+fn description(comment: &str) -> String {
+    let mut out = String::new();
+    for line in comment.lines() {
+        let line = line.trim_start();
+        let line = line.strip_prefix('*').unwrap_or(line);
+        if line.trim_start().starts_with('@') {
+            break;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+struct Resolver<'p, 'a> {
+    parser: &'p Parser<'a>,
+    outer: &'p [String],
+}
+
+impl Resolve for Resolver<'_, '_> {
+    fn class(&self, name: &str) -> Vec<String> {
+        let mut candidates = Vec::new();
+        if name.is_empty() {
+            candidates.extend(self.outer.last().cloned());
+            return candidates;
+        }
+        let segments: Vec<&str> = name.split('.').collect();
+        let nested = segments[1..].iter().map(|s| format!("${s}")).collect::<String>();
+
+        // Nested classes of the enclosing classes, innermost first.
+        for outer in self.outer.iter().rev() {
+            candidates.push(format!("{outer}${}{nested}", segments[0]));
+        }
+        if let Some(import) = self.parser.imports.get(segments[0]) {
+            candidates.push(format!("{import}{nested}"));
+        }
+        if !self.parser.package.is_empty() {
+            candidates.push(format!("{}/{}{nested}", self.parser.package, segments[0]));
+        }
+        for package in &self.parser.wildcard_imports {
+            candidates.push(format!("{package}/{}{nested}", segments[0]));
+        }
+        candidates.push(format!("java/lang/{}{nested}", segments[0]));
+        // Qualified names, where the package ends at any segment.
+        for i in 1..segments.len() {
+            let package = segments[..i].join("/");
+            let class = segments[i..].join("$");
+            candidates.push(format!("{package}/{class}"));
+        }
+        if self.parser.package.is_empty() {
+            candidates.push(segments.join("$"));
+        }
+        candidates
+    }
+
+    fn href(&self, _href: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        match ch {
+            ch if ch.is_whitespace() => {}
+            '/' if chars.peek().is_some_and(|&(_, next)| next == '/') => {
+                for (_, ch) in chars.by_ref() {
+                    if ch == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().is_some_and(|&(_, next)| next == '*') => {
+                chars.next();
+                let body_start = start + 2;
+                let mut end = source.len();
+                let mut prev = ' ';
+                for (i, ch) in chars.by_ref() {
+                    if prev == '*' && ch == '/' && i > body_start {
+                        end = i - 1;
+                        break;
+                    }
+                    prev = ch;
+                }
+                let body = &source[body_start..end.max(body_start)];
+                if let Some(doc) = body.strip_prefix('*')
+                    && !doc.is_empty()
+                {
+                    tokens.push(Token::Doc(doc));
+                }
+            }
+            '"' if source[start..].starts_with("\"\"\"") => {
+                chars.next();
+                chars.next();
+                let mut quotes = 0;
+                let mut escaped = false;
+                for (_, ch) in chars.by_ref() {
+                    match ch {
+                        '"' if !escaped => {
+                            quotes += 1;
+                            if quotes == 3 {
+                                break;
+                            }
+                        }
+                        '\\' if !escaped => {
+                            escaped = true;
+                            quotes = 0;
+                            continue;
+                        }
+                        _ => quotes = 0,
+                    }
+                    escaped = false;
+                }
+                tokens.push(Token::Literal);
+            }
+            '"' | '\'' => {
+                let mut escaped = false;
+                for (_, c) in chars.by_ref() {
+                    match c {
+                        '\\' if !escaped => {
+                            escaped = true;
+                            continue;
+                        }
+                        '\n' => break,
+                        c if c == ch && !escaped => break,
+                        _ => {}
+                    }
+                    escaped = false;
+                }
+                tokens.push(Token::Literal);
+            }
+            ch if ch.is_ascii_digit() => {
+                while chars
+                    .peek()
+                    .is_some_and(|&(_, c)| c.is_alphanumeric() || c == '_' || c == '.')
+                {
+                    chars.next();
+                }
+                tokens.push(Token::Literal);
+            }
+            ch if ch.is_alphabetic() || ch == '_' || ch == '$' => {
+                let mut end = start + ch.len_utf8();
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '$') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Ident(&source[start..end]));
+            }
+            ch => tokens.push(Token::Punct(ch)),
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let source = r#"
+            package com.example;
+
+            import java.util.List;
+
+            /** A widget, see {@link List}. */
+            @Deprecated(since = "1")
+            public class Widget<T> implements Comparable<Widget<T>> {
+                /** The default size. */
+                public static final int SIZE = 3, OTHER_SIZE = SIZE * 2;
+                /** Names, by id. */
+                public java.util.Map<String, Integer> names = new java.util.HashMap<>() {{ put("x", 1); }};
+
+                /**
+                 * Creates a widget.
+                 *
+                 * @param name the name
+                 */
+                public Widget(String name) { this.name = name; }
+
+                /** Sets the items. <p>Details. */
+                public <U extends T> void setItems(final List<? super U> items, @Nullable int[] ids, String... tags) {
+                    Runnable r = () -> { /** not a doc */ };
+                }
+
+                /** Undocumented {@inheritDoc} */
+                public int compareTo(Widget<T> other) { return 0; }
+
+                /** A nested enum. */
+                public enum Kind {
+                    /** The first kind. */
+                    FIRST("a") { void f() {} },
+                    SECOND;
+
+                    /** Gets the value. */
+                    abstract String value();
+                }
+            }
+        "#;
+        let classes = parse(source, "com/example/Widget");
+        let (path, widget) = &classes[0];
+        assert_eq!(path, "com/example/Widget");
+        assert!(widget.doc.is_some());
+        assert_eq!(
+            widget
+                .fields
+                .keys()
+                .map(String::as_str)
+                .collect::<std::collections::BTreeSet<_>>(),
+            ["OTHER_SIZE", "SIZE", "names"].into()
+        );
+        let methods: Vec<_> = widget
+            .methods
+            .iter()
+            .map(|m| (m.name.as_str(), m.params.clone()))
+            .collect();
+        assert_eq!(
+            methods,
+            [
+                ("<init>", vec!["String".to_string()]),
+                (
+                    "setItems",
+                    vec!["List".to_string(), "int[]".to_string(), "String[]".to_string()]
+                ),
+            ]
+        );
+
+        let (path, kind) = &classes[1];
+        assert_eq!(path, "com/example/Widget$Kind");
+        assert!(kind.doc.is_some());
+        assert!(kind.fields.contains_key("FIRST"));
+        assert!(!kind.fields.contains_key("SECOND"));
+        assert_eq!(kind.methods[0].name, "value");
+    }
+}
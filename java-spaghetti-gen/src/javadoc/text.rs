@@ -0,0 +1,488 @@
+//! Conversion of Javadoc comments and javadoc HTML, with inline tags such as `{@link}`, into a [Doc].
+
+use super::{Doc, DocPart};
+
+/// Resolves the references of links in a documentation comment.
+pub(super) trait Resolve {
+    /// Returns candidate JNI paths for a class referenced by name, e.g. `String`, `Map.Entry` or `java.util.List`.
+    /// An empty name refers to the documented class itself.
+    fn class(&self, name: &str) -> Vec<String>;
+
+    /// Returns candidate JNI paths for the class an `<a href>` points to.
+    fn href(&self, href: &str) -> Vec<String>;
+}
+
+/// HTML elements ending the first paragraph of a description.
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "ul",
+    "ol",
+    "dl",
+    "pre",
+    "table",
+    "blockquote",
+    "div",
+    "hr",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+];
+
+/// Converts the first paragraph of a description to a [Doc], or returns `None` if it is empty or inherited.
+pub(super) fn summary(html: &str, resolve: &impl Resolve) -> Option<Doc> {
+    let mut parser = Parser {
+        input: html,
+        pos: 0,
+        parts: Vec::new(),
+        resolve,
+    };
+    parser.run()?;
+
+    let mut parts: Vec<DocPart> = Vec::new();
+    for part in parser.parts {
+        match (parts.last_mut(), part) {
+            (Some(DocPart::Text(last)), DocPart::Text(text)) => last.push_str(&text),
+            (_, part) => parts.push(part),
+        }
+    }
+    if let Some(DocPart::Text(text)) = parts.first_mut() {
+        *text = text.trim_start().to_string();
+    }
+    if let Some(DocPart::Text(text)) = parts.last_mut() {
+        *text = text.trim_end().to_string();
+    }
+    parts.retain(|part| !matches!(part, DocPart::Text(text) if text.is_empty()));
+    (!parts.is_empty()).then_some(Doc(parts))
+}
+
+struct Parser<'a, R> {
+    input: &'a str,
+    pos: usize,
+    parts: Vec<DocPart>,
+    resolve: &'a R,
+}
+
+impl<'a, R: Resolve> Parser<'a, R> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn has_content(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|part| !matches!(part, DocPart::Text(text) if text.trim().is_empty()))
+    }
+
+    fn text(&mut self, text: &str) {
+        let mut out = String::with_capacity(text.len());
+        for ch in text.chars() {
+            if ch.is_whitespace() {
+                if !out.ends_with(' ') {
+                    out.push(' ');
+                }
+            } else {
+                out.push(ch);
+            }
+        }
+        if matches!(self.parts.last(), Some(DocPart::Text(last)) if last.ends_with(' ')) {
+            out = out.trim_start().to_string();
+        }
+        self.parts.push(DocPart::Text(out));
+    }
+
+    /// Returns `None` for `{@inheritDoc}`, as the inherited documentation isn't known.
+    fn run(&mut self) -> Option<()> {
+        while self.pos < self.input.len() {
+            let rest = self.rest();
+            let next = rest.find(['<', '{', '&']).unwrap_or(rest.len());
+            if next > 0 {
+                let text = rest[..next].to_string();
+                self.text(&text);
+                self.pos += next;
+                continue;
+            }
+            if rest.starts_with("{@") {
+                self.inline_tag()?;
+            } else if rest.starts_with('&') {
+                let (ch, len) = entity(rest);
+                self.text(&ch);
+                self.pos += len;
+            } else if rest.starts_with('<') {
+                let Some(tag) = Tag::parse(rest) else {
+                    self.text("<");
+                    self.pos += 1;
+                    continue;
+                };
+                self.pos += tag.len;
+                if BLOCK_TAGS.contains(&tag.name.as_str()) {
+                    if self.has_content() {
+                        break;
+                    }
+                    continue;
+                }
+                match (tag.name.as_str(), tag.closing) {
+                    ("code" | "tt" | "samp" | "kbd" | "var", false) => {
+                        let code = self.until_closing(&tag.name);
+                        self.code(&code);
+                    }
+                    ("a", false) => self.anchor(&tag),
+                    ("br", _) => self.text(" "),
+                    _ => {}
+                }
+            } else {
+                self.text("{");
+                self.pos += 1;
+            }
+        }
+        Some(())
+    }
+
+    /// Parses `{@tag content}`, where braces in the content are balanced.
+    fn inline_tag(&mut self) -> Option<()> {
+        let rest = self.rest();
+        let mut depth = 0;
+        let end = rest.char_indices().find_map(|(i, ch)| {
+            match ch {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        });
+        let Some(end) = end else {
+            self.text(rest);
+            self.pos = self.input.len();
+            return Some(());
+        };
+        let inner = rest[2..end].to_string();
+        self.pos += end + 1;
+
+        let (tag, content) = inner.split_once(char::is_whitespace).unwrap_or((&inner, ""));
+        let content = content.trim();
+        match tag {
+            "inheritDoc" => return None,
+            "code" | "value" if !content.is_empty() => self.parts.push(DocPart::Code(content.to_string())),
+            "literal" | "summary" | "index" => self.text(content),
+            "link" | "linkplain" => {
+                let (reference, label) = split_reference(content);
+                let (class, member) = reference.split_once('#').unwrap_or((reference, ""));
+                let text = match label {
+                    Some(label) => label.to_string(),
+                    None if member.is_empty() => class.to_string(),
+                    None if class.is_empty() => member.to_string(),
+                    None => format!("{class}.{member}"),
+                };
+                let class = class.rsplit_once('/').map_or(class, |(_module, class)| class);
+                self.parts.push(DocPart::Link {
+                    classes: self.resolve.class(class),
+                    text,
+                    code: tag == "link" && label.is_none(),
+                });
+            }
+            _ => {}
+        }
+        Some(())
+    }
+
+    /// `<code>` naming a class, by convention capitalized, is converted to a link.
+    fn code(&mut self, code: &str) {
+        let code = code.split_whitespace().collect::<Vec<_>>().join(" ");
+        let is_name = code.split('.').all(|segment| {
+            segment.starts_with(char::is_alphabetic)
+                && segment.chars().all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '$')
+        });
+        let is_class = code
+            .rsplit('.')
+            .next()
+            .is_some_and(|name| name.starts_with(char::is_uppercase));
+        let classes = match is_name && is_class {
+            true => self.resolve.class(&code),
+            false => Vec::new(),
+        };
+        self.parts.push(match classes.is_empty() {
+            true => DocPart::Code(code),
+            false => DocPart::Link {
+                classes,
+                text: code,
+                code: true,
+            },
+        });
+    }
+
+    fn anchor(&mut self, tag: &Tag) {
+        let start = self.pos;
+        let inner = self.until_closing("a");
+        let is_code = self.input[start..self.pos].contains("<code>");
+        match tag.attribute("href") {
+            Some(href) if href.starts_with("http://") || href.starts_with("https://") => {
+                self.parts.push(DocPart::Url {
+                    url: href.to_string(),
+                    text: inner,
+                })
+            }
+            Some(href) => {
+                let classes = self.resolve.href(&href);
+                match classes.is_empty() {
+                    true if is_code => self.parts.push(DocPart::Code(inner)),
+                    true => self.text(&inner),
+                    false => self.parts.push(DocPart::Link {
+                        classes,
+                        text: inner,
+                        code: is_code,
+                    }),
+                }
+            }
+            None => self.text(&inner),
+        }
+    }
+
+    /// Returns the text up to the closing tag, without markup, and skips past it.
+    fn until_closing(&mut self, name: &str) -> String {
+        let mut out = String::new();
+        while self.pos < self.input.len() {
+            let rest = self.rest();
+            if rest.starts_with('<')
+                && let Some(tag) = Tag::parse(rest)
+            {
+                self.pos += tag.len;
+                if tag.closing && tag.name == name {
+                    break;
+                }
+            } else if rest.starts_with('&') {
+                let (ch, len) = entity(rest);
+                out.push_str(&ch);
+                self.pos += len;
+            } else if let Some(inner) = rest.strip_prefix("{@")
+                && let Some(end) = inner.find('}')
+            {
+                // `{@code}` and `{@literal}` are common inside `<code>`, other tags are rare.
+                let (_tag, content) = inner[..end]
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((&inner[..end], ""));
+                out.push_str(content.trim());
+                self.pos += 2 + end + 1;
+            } else {
+                let ch = rest.chars().next().unwrap();
+                out.push(ch);
+                self.pos += ch.len_utf8();
+            }
+        }
+        out.trim().to_string()
+    }
+}
+
+/// Splits the reference of a `{@link}` from its label, e.g. `Map#put(Object, Object) the put method`.
+fn split_reference(content: &str) -> (&str, Option<&str>) {
+    let mut depth = 0;
+    for (i, ch) in content.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ch if ch.is_whitespace() && depth == 0 => {
+                let label = content[i..].trim();
+                return (&content[..i], (!label.is_empty()).then_some(label));
+            }
+            _ => {}
+        }
+    }
+    (content, None)
+}
+
+/// Decodes an HTML entity at the start of `s`, returning the text and the length consumed.
+fn entity(s: &str) -> (String, usize) {
+    let Some(end) = s.find(';').filter(|&end| end <= 10) else {
+        return ("&".to_string(), 1);
+    };
+    let decoded = match &s[1..end] {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        num => num
+            .strip_prefix("#x")
+            .or_else(|| num.strip_prefix("#X"))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .or_else(|| num.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+            .and_then(char::from_u32),
+    };
+    match decoded {
+        Some(ch) => (ch.to_string(), end + 1),
+        None => ("&".to_string(), 1),
+    }
+}
+
+/// An HTML start or end tag.
+pub(super) struct Tag<'a> {
+    /// Lowercase element name.
+    pub name: String,
+    pub closing: bool,
+    attributes: &'a str,
+    /// Length of the tag in the input, including the angle brackets.
+    pub len: usize,
+}
+
+impl<'a> Tag<'a> {
+    /// Parses the tag at the start of `s`, which must start with `<`. Comments are returned as tags named `!--`.
+    pub fn parse(s: &'a str) -> Option<Self> {
+        if s.starts_with("<!--") {
+            let len = s.find("-->").map_or(s.len(), |end| end + 3);
+            return Some(Self {
+                name: "!--".to_string(),
+                closing: false,
+                attributes: "",
+                len,
+            });
+        }
+        let rest = &s[1..];
+        let (closing, rest) = match rest.strip_prefix('/') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let name_len = rest.find(|ch: char| !ch.is_ascii_alphanumeric()).unwrap_or(rest.len());
+        if name_len == 0 || !rest.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        // Find the end of the tag, skipping over quoted attribute values.
+        let mut quote = None;
+        let end = rest.char_indices().find_map(|(i, ch)| {
+            match (quote, ch) {
+                (None, '"' | '\'') => quote = Some(ch),
+                (Some(q), ch) if q == ch => quote = None,
+                (None, '>') => return Some(i),
+                _ => {}
+            }
+            None
+        })?;
+        Some(Self {
+            name: rest[..name_len].to_ascii_lowercase(),
+            closing,
+            attributes: &rest[name_len..end],
+            len: s.len() - rest.len() + end + 1,
+        })
+    }
+
+    /// Returns the value of an attribute, with entities decoded.
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        let mut rest = self.attributes;
+        loop {
+            rest = rest.trim_start_matches(|ch: char| ch.is_whitespace() || ch == '/');
+            let name_len = rest.find(|ch: char| ch.is_whitespace() || ch == '=' || ch == '>')?;
+            if name_len == 0 {
+                return None;
+            }
+            let attr = &rest[..name_len];
+            rest = rest[name_len..].trim_start();
+            let value = if let Some(after) = rest.strip_prefix('=') {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(q @ ('"' | '\'')) => {
+                        let end = after[1..].find(q)? + 1;
+                        (&after[1..end], &after[end + 1..])
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining;
+                value
+            } else {
+                ""
+            };
+            if attr.eq_ignore_ascii_case(name) {
+                return Some(decode_entities(value));
+            }
+        }
+    }
+}
+
+pub(super) fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        let (ch, len) = entity(&rest[i..]);
+        out.push_str(&ch);
+        rest = &rest[i + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Classes;
+
+    impl Resolve for Classes {
+        fn class(&self, name: &str) -> Vec<String> {
+            vec![format!("pkg/{}", name.replace('.', "$"))]
+        }
+
+        fn href(&self, href: &str) -> Vec<String> {
+            let page = href.split('#').next().unwrap();
+            vec![page.trim_end_matches(".html").to_string()]
+        }
+    }
+
+    fn link(class: &str, text: &str, code: bool) -> DocPart {
+        DocPart::Link {
+            classes: vec![class.to_string()],
+            text: text.to_string(),
+            code,
+        }
+    }
+
+    #[test]
+    fn summary_test() {
+        let doc = summary(
+            "Returns the {@code List} of\n   entries, see {@link Map.Entry#getKey()} and\n\
+             {@linkplain Other other things} &amp; <code>Foo</code>.\n<p>More details.",
+            &Classes,
+        )
+        .unwrap();
+        assert_eq!(
+            doc.0,
+            [
+                DocPart::Text("Returns the ".to_string()),
+                DocPart::Code("List".to_string()),
+                DocPart::Text(" of entries, see ".to_string()),
+                link("pkg/Map$Entry", "Map.Entry.getKey()", true),
+                DocPart::Text(" and ".to_string()),
+                link("pkg/Other", "other things", false),
+                DocPart::Text(" & ".to_string()),
+                link("pkg/Foo", "Foo", true),
+                DocPart::Text(".".to_string()),
+            ]
+        );
+
+        let doc = summary(
+            "<p>Sets a <a href=\"../Bar.html#x\"><code>Bar</code></a> (<a href=\"https://example.com\">spec</a>).</p>",
+            &Classes,
+        )
+        .unwrap();
+        assert_eq!(
+            doc.0,
+            [
+                DocPart::Text("Sets a ".to_string()),
+                link("../Bar", "Bar", true),
+                DocPart::Text(" (".to_string()),
+                DocPart::Url {
+                    url: "https://example.com".to_string(),
+                    text: "spec".to_string(),
+                },
+                DocPart::Text(").".to_string()),
+            ]
+        );
+
+        assert!(summary("{@inheritDoc}", &Classes).is_none());
+        assert!(summary("  <p>  ", &Classes).is_none());
+    }
+}
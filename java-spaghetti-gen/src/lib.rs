@@ -0,0 +1,519 @@
+//! Generates Rust bindings to Java APIs, from the `java-spaghetti-gen` command or from build scripts with [`Builder`].
+
+// this must go first because of macros.
+mod util;
+
+mod builder;
+mod cache;
+pub mod config;
+mod emit;
+mod identifiers;
+mod javadoc;
+mod parser_util;
+#[cfg(test)]
+mod testing;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek};
+use std::path::Path;
+use std::sync::Mutex;
+
+pub use crate::builder::Builder;
+use crate::config::{Config, OutputLayout};
+use crate::parser_util::JavaClass;
+
+/// The core function of this library: Generate Rust code to access Java APIs.
+pub fn run(config: impl Into<Config>) -> Result<(), anyhow::Error> {
+    let config: Config = config.into();
+    println!("output: {}", config.output.display());
+
+    let mut context = emit::Context::new(&config);
+    let mut cache = None;
+    if let Some(path) = &config.cache {
+        let previous = cache::Cache::read(path);
+        let current = cache::Cache::new(&config)?;
+        if current.unchanged(&previous) && outputs_exist(&config) {
+            context
+                .progress
+                .lock()
+                .unwrap()
+                .force_update(format!("unchanged: {} (cached)", config.output.display()).as_str());
+            return Ok(());
+        }
+        cache = Some((current, previous));
+    }
+
+    for file in config.input.iter() {
+        gather_file(&mut context, file)?;
+    }
+    context.include_dependencies()?;
+    if let Some((mut current, previous)) = cache {
+        current.add_classes(&context, previous);
+        context.cache = Some(Mutex::new(current));
+    }
+
+    let mut javadoc = javadoc::Javadoc::default();
+    for file in config.sources.iter() {
+        context
+            .progress
+            .lock()
+            .unwrap()
+            .update(format!("reading {}...", file.display()).as_str());
+        javadoc.read(file, &context)?;
+    }
+    context.javadoc = javadoc;
+
+    match config.output_layout {
+        OutputLayout::SingleFile | OutputLayout::Include => {
+            let mut out = Vec::with_capacity(4096);
+            context.write(&mut out)?;
+            util::write_generated(&context, &config.output, &out[..])?;
+        }
+        OutputLayout::PerPackage => context.write_dir(&config.output)?,
+    }
+
+    if let Some(report) = &config.report {
+        context.report.lock().unwrap().write(report)?;
+    }
+    if let (Some(path), Some(cache)) = (&config.cache, context.cache.take()) {
+        cache.into_inner().unwrap().write(path)?;
+    }
+
+    // Generate Java proxy files if proxy_output is specified
+    if let Some(proxy_output) = &config.proxy_output {
+        emit::java_proxy::write_java_proxy_files(&context, proxy_output)?;
+    }
+
+    Ok(())
+}
+
+/// Whether the files written by a previous run are still there, for skipping unchanged runs.
+fn outputs_exist(config: &Config) -> bool {
+    let outputs = [
+        Some(&config.output),
+        config.report.as_ref(),
+        config.proxy_output.as_ref(),
+    ];
+    outputs.into_iter().flatten().all(|path| path.exists())
+}
+
+fn gather_file(context: &mut emit::Context, path: &Path) -> Result<(), anyhow::Error> {
+    let verbose = context.config.logging_verbose;
+
+    context
+        .progress
+        .lock()
+        .unwrap()
+        .update(format!("reading {}...", path.display()).as_str());
+
+    if path.is_dir() {
+        return gather_dir(context, path);
+    }
+
+    // The runtime image of JDK 9+ keeps all of its classes in the extensionless `lib/modules` jimage.
+    if path.extension().is_none() && path.file_name().is_some_and(|name| name == "modules") {
+        return gather_jimage(context, path);
+    }
+
+    let ext = if let Some(ext) = path.extension() {
+        ext
+    } else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Input files must have an extension",
+        ))?;
+    };
+
+    match ext.to_string_lossy().to_ascii_lowercase().as_str() {
+        "class" => {
+            let class = JavaClass::read(std::fs::read(path)?)?;
+            context.add_class(class)?;
+        }
+        "jar" => {
+            let jar = zip::ZipArchive::new(io::BufReader::new(File::open(path)?))?;
+            gather_jar(context, jar, "")?;
+        }
+        "jmod" => {
+            let module = path.file_stem().unwrap_or_default().to_string_lossy();
+            if !context.config.includes_module(&module) {
+                return Ok(());
+            }
+
+            // A jmod file is a zip archive behind a 4-byte "JM" header, with class files under `classes/`.
+            let data = std::fs::read(path)?;
+            if !data.starts_with(b"JM\x01\x00") {
+                return Err(anyhow::anyhow!("{} is not a jmod file: bad header", path.display()));
+            }
+            let jmod = zip::ZipArchive::new(io::Cursor::new(&data[4..]))?;
+            gather_jar(context, jmod, "classes/")?;
+        }
+        "aar" => {
+            let mut aar = zip::ZipArchive::new(io::BufReader::new(File::open(path)?))?;
+
+            // Android archives carry their classes in `classes.jar`, plus optional dependency jars under `libs/`.
+            let mut nested_jars: Vec<String> = aar
+                .file_names()
+                .filter(|name| *name == "classes.jar" || (name.starts_with("libs/") && name.ends_with(".jar")))
+                .map(|name| name.to_owned())
+                .collect();
+            nested_jars.sort();
+
+            for name in nested_jars {
+                if verbose {
+                    context
+                        .progress
+                        .lock()
+                        .unwrap()
+                        .update(format!("  reading {}!/{name}...", path.display()).as_str());
+                }
+
+                let mut buf = Vec::new();
+                aar.by_name(&name)?.read_to_end(&mut buf)?;
+                let jar = zip::ZipArchive::new(io::Cursor::new(buf))?;
+                gather_jar(context, jar, "")?;
+            }
+        }
+        unknown => {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Input files must have a '.class', '.jar', '.aar' or '.jmod' extension, not a '.{unknown}' extension",
+                ),
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Walks the directory recursively for class files, and jar files if `read_jars_in_directories` is enabled.
+fn gather_dir(context: &mut emit::Context, path: &Path) -> Result<(), anyhow::Error> {
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort(); // keeps the output independent of directory iteration order
+
+    for entry in entries {
+        if entry.is_dir() {
+            gather_dir(context, &entry)?;
+            continue;
+        }
+        let ext = entry
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "class" => gather_file(context, &entry)?,
+            "jar" | "aar" if context.config.read_jars_in_directories => gather_file(context, &entry)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Reads all class files under `prefix` in the zip archive.
+///
+/// For multi-release jars, the entry under `META-INF/versions/N/` with the highest `N` not exceeding
+/// `java_release` replaces the base entry of the same class.
+fn gather_jar(
+    context: &mut emit::Context,
+    mut jar: zip::ZipArchive<impl Read + Seek>,
+    prefix: &str,
+) -> Result<(), anyhow::Error> {
+    let verbose = context.config.logging_verbose;
+    let release = match context.config.java_release {
+        Some(release) if is_multi_release(&mut jar)? => Some(release),
+        _ => None,
+    };
+
+    // class file name => (release version, zip entry index)
+    let mut selected: HashMap<String, (u32, usize)> = HashMap::new();
+    for (i, name) in jar.file_names().enumerate() {
+        let Some(name) = name.strip_prefix(prefix) else {
+            continue;
+        };
+        let Some((version, name)) = jar_class_entry(name, release) else {
+            continue;
+        };
+        match selected.get(name) {
+            Some(&(v, _)) if v >= version => {}
+            _ => {
+                selected.insert(name.to_string(), (version, i));
+            }
+        }
+    }
+    let mut indices: Vec<usize> = selected.into_values().map(|(_, i)| i).collect();
+    indices.sort();
+
+    let n = jar.len();
+    for i in indices {
+        let mut file = jar.by_index(i)?;
+
+        if verbose {
+            context
+                .progress
+                .lock()
+                .unwrap()
+                .update(format!("  reading {:3}/{}: {}...", i, n, file.name()).as_str());
+        }
+
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        let class = JavaClass::read(buf)?;
+        context.add_class(class)?;
+    }
+    Ok(())
+}
+
+fn is_multi_release(jar: &mut zip::ZipArchive<impl Read + Seek>) -> Result<bool, anyhow::Error> {
+    let mut manifest = String::new();
+    match jar.by_name("META-INF/MANIFEST.MF") {
+        Ok(mut file) => file.read_to_string(&mut manifest)?,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(manifest.lines().any(|line| {
+        line.split_once(':').is_some_and(|(key, value)| {
+            key.trim().eq_ignore_ascii_case("Multi-Release") && value.trim().eq_ignore_ascii_case("true")
+        })
+    }))
+}
+
+/// Returns the release version (0 for base entries) and the class file name of a jar entry, or `None` if the
+/// entry is not a class file applicable to `release`.
+fn jar_class_entry(name: &str, release: Option<u32>) -> Option<(u32, &str)> {
+    let (version, name) = if let Some(rest) = name.strip_prefix("META-INF/") {
+        let (version, name) = rest.strip_prefix("versions/")?.split_once('/')?;
+        let version: u32 = version.parse().ok()?;
+        if version > release? {
+            return None;
+        }
+        (version, name)
+    } else {
+        (0, name)
+    };
+
+    if !name.ends_with(".class") || name == "module-info.class" {
+        return None;
+    }
+    Some((version, name))
+}
+
+fn gather_jimage(context: &mut emit::Context, path: &Path) -> Result<(), anyhow::Error> {
+    let verbose = context.config.logging_verbose;
+    let image = util::JImage::read(std::fs::read(path)?)?;
+
+    let mut compressed = 0;
+    for entry in image.entries() {
+        let entry = entry?;
+        if !entry.name.ends_with(".class")
+            || entry.name == "module-info.class"
+            || !context.config.includes_module(&entry.module)
+        {
+            continue;
+        }
+
+        // Compressed resources cannot be read, skip them rather than failing on the first one.
+        if entry.is_compressed() {
+            if verbose {
+                context
+                    .progress
+                    .lock()
+                    .unwrap()
+                    .force_update(format!("  skipping compressed /{}/{}", entry.module, entry.name).as_str());
+            }
+            compressed += 1;
+            continue;
+        }
+
+        if verbose {
+            context
+                .progress
+                .lock()
+                .unwrap()
+                .update(format!("  reading /{}/{}...", entry.module, entry.name).as_str());
+        }
+
+        let class = JavaClass::read(image.content(&entry)?.to_vec())?;
+        context.add_class(class)?;
+    }
+
+    if compressed > 0 {
+        context.progress.lock().unwrap().force_update(
+            format!(
+                "warning: skipped {compressed} compressed classes in {}, use an image linked without `--compress` \
+                 or the jmods of the JDK",
+                path.display()
+            )
+            .as_str(),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{ClassFile, TempDir, zip};
+    use crate::util::JImageResource;
+
+    fn gathered_classes(path: &Path) -> Vec<String> {
+        let mut config = Config::default();
+        config.add_default_rule();
+        let mut context = emit::Context::new(&config);
+        gather_file(&mut context, path).unwrap();
+        let mut classes: Vec<_> = context.all_classes.into_keys().collect();
+        classes.sort();
+        classes
+    }
+
+    #[test]
+    fn jmod() {
+        let classes = zip(&[
+            (
+                "classes/java/lang/Foo.class",
+                ClassFile::new("java/lang/Foo").to_bytes(),
+            ),
+            ("classes/module-info.class", b"module info".to_vec()),
+            // Only classes under `classes/` are read.
+            ("lib/java/lang/Bar.class", b"not a class".to_vec()),
+        ]);
+        let mut data = b"JM\x01\x00".to_vec();
+        data.extend_from_slice(&classes);
+
+        let dir = TempDir::new("jmod");
+        let path = dir.path().join("java.base.jmod");
+        std::fs::write(&path, &data).unwrap();
+        assert_eq!(gathered_classes(&path), ["java/lang/Foo"]);
+
+        // Without the header, this is only a zip file.
+        std::fs::write(&path, &classes).unwrap();
+        let config = Config::default();
+        assert!(gather_file(&mut emit::Context::new(&config), &path).is_err());
+    }
+
+    #[test]
+    fn jimage_skips_compressed_classes() {
+        let object = ClassFile::new("java/lang/Object").to_bytes();
+        let data = util::build_jimage(&[
+            JImageResource::new("java.base", "java/lang/String.class", b"zipped").compressed(),
+            JImageResource::new("java.base", "java/lang/Object.class", &object),
+        ]);
+
+        let dir = TempDir::new("jimage");
+        let path = dir.path().join("modules");
+        std::fs::write(&path, data).unwrap();
+        assert_eq!(gathered_classes(&path), ["java/lang/Object"]);
+    }
+
+    #[test]
+    fn aar() {
+        let classes_jar = zip(&[
+            ("com/example/Foo.class", ClassFile::new("com/example/Foo").to_bytes()),
+            ("META-INF/MANIFEST.MF", b"Manifest-Version: 1.0\n".to_vec()),
+        ]);
+        let dep_jar = zip(&[(
+            "com/example/dep/Bar.class",
+            ClassFile::new("com/example/dep/Bar").to_bytes(),
+        )]);
+        let aar = zip(&[
+            ("AndroidManifest.xml", b"<manifest/>".to_vec()),
+            ("classes.jar", classes_jar),
+            ("libs/dep.jar", dep_jar),
+            // Only jars directly named `classes.jar` or under `libs/` are read.
+            ("assets/other.jar", b"not a jar".to_vec()),
+            ("libs/readme.txt", b"not a jar".to_vec()),
+        ]);
+
+        let dir = TempDir::new("aar");
+        let path = dir.path().join("library.aar");
+        std::fs::write(&path, aar).unwrap();
+        assert_eq!(gathered_classes(&path), ["com/example/Foo", "com/example/dep/Bar"]);
+    }
+
+    #[test]
+    fn classes_directory() {
+        let dir = TempDir::new("classes_dir");
+        let classes = dir.path().join("classes");
+        let files = [
+            ("com/example/Foo.class", ClassFile::new("com/example/Foo").to_bytes()),
+            (
+                "com/example/inner/Bar.class",
+                ClassFile::new("com/example/inner/Bar").to_bytes(),
+            ),
+            ("org/other/Baz.class", ClassFile::new("org/other/Baz").to_bytes()),
+            ("com/example/notes.txt", b"not a class".to_vec()),
+            (
+                "libs/dep.jar",
+                zip(&[(
+                    "com/example/dep/Dep.class",
+                    ClassFile::new("com/example/dep/Dep").to_bytes(),
+                )]),
+            ),
+        ];
+        for (name, content) in files {
+            let path = classes.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        // Jars are only read with `read_jars_in_directories`.
+        assert_eq!(
+            gathered_classes(&classes),
+            ["com/example/Foo", "com/example/inner/Bar", "org/other/Baz"]
+        );
+
+        let mut config = Config {
+            read_jars_in_directories: true,
+            ..Config::default()
+        };
+        config.add_default_rule();
+        let mut context = emit::Context::new(&config);
+        gather_file(&mut context, &classes).unwrap();
+        assert!(context.all_classes.contains_key("com/example/dep/Dep"));
+
+        let mut out = Vec::new();
+        context.write(&mut out).unwrap();
+        let code = String::from_utf8(out).unwrap();
+        for module in [
+            "pub mod com",
+            "pub mod example",
+            "pub mod inner",
+            "pub mod dep",
+            "pub mod other",
+        ] {
+            assert!(code.contains(module), "{module}: {code}");
+        }
+        assert!(code.contains("pub enum Bar"));
+    }
+
+    #[test]
+    fn jar_class_entry_test() {
+        assert_eq!(
+            jar_class_entry("com/example/Foo.class", None),
+            Some((0, "com/example/Foo.class"))
+        );
+        assert_eq!(jar_class_entry("com/example/Foo.txt", None), None);
+        assert_eq!(jar_class_entry("module-info.class", None), None);
+        assert_eq!(jar_class_entry("META-INF/MANIFEST.MF", Some(17)), None);
+
+        // Versioned entries are only applicable up to the target release.
+        assert_eq!(
+            jar_class_entry("META-INF/versions/11/com/example/Foo.class", None),
+            None
+        );
+        assert_eq!(
+            jar_class_entry("META-INF/versions/11/com/example/Foo.class", Some(9)),
+            None
+        );
+        assert_eq!(
+            jar_class_entry("META-INF/versions/11/com/example/Foo.class", Some(11)),
+            Some((11, "com/example/Foo.class"))
+        );
+        assert_eq!(jar_class_entry("META-INF/versions/9/module-info.class", Some(17)), None);
+        assert_eq!(
+            jar_class_entry("META-INF/versions/x/com/example/Foo.class", Some(17)),
+            None
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomPinned;
 use std::pin::Pin;
 
@@ -5,11 +6,11 @@ pub use cafebabe::ClassAccessFlags;
 use cafebabe::attributes::AttributeData;
 use cafebabe::descriptors::ClassName;
 
-use super::Id;
+use super::{ClassSignature, Id};
+use crate::util::StableHasher;
 
 #[derive(Debug)]
 pub struct JavaClass {
-    #[allow(unused)]
     raw_bytes: Pin<Box<(Vec<u8>, PhantomPinned)>>,
     inner: cafebabe::ClassFile<'static>,
 }
@@ -27,6 +28,13 @@ impl JavaClass {
         })
     }
 
+    /// Hash of the class file, see `Config::cache`.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = StableHasher::default();
+        self.raw_bytes.0.hash(&mut hasher);
+        hasher.finish()
+    }
+
     // It is probably not possible to implement `Deref` safely.
     pub fn get<'a>(&'a self) -> &'a cafebabe::ClassFile<'a> {
         // SAFETY: casts `self.inner` into `cafebabe::ClassFile<'a>` forcefully.
@@ -97,4 +105,12 @@ impl JavaClass {
             .iter()
             .any(|attr| matches!(attr.data, AttributeData::Deprecated))
     }
+
+    /// Returns the generic signature, if the class has a valid one.
+    pub fn signature(&self) -> Option<ClassSignature> {
+        self.get().attributes.iter().find_map(|attr| match &attr.data {
+            AttributeData::Signature(s) => ClassSignature::parse(s),
+            _ => None,
+        })
+    }
 }
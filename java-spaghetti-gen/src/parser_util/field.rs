@@ -3,6 +3,8 @@ use cafebabe::attributes::AttributeData;
 use cafebabe::constant_pool::LiteralConstant;
 use cafebabe::descriptors::FieldDescriptor;
 
+use super::{Nullability, TypeSignature};
+
 #[derive(Clone, Copy, Debug)]
 pub struct JavaField<'a> {
     java: &'a cafebabe::FieldInfo<'a>,
@@ -91,4 +93,16 @@ impl<'a> JavaField<'a> {
     pub fn descriptor<'s>(&'s self) -> &'a FieldDescriptor<'a> {
         &self.java.descriptor
     }
+
+    /// Returns the generic signature, if the field has a valid one.
+    pub fn signature(&self) -> Option<TypeSignature> {
+        self.attributes.iter().find_map(|attr| match &attr.data {
+            AttributeData::Signature(s) => TypeSignature::parse(s),
+            _ => None,
+        })
+    }
+
+    pub fn nullability(&self) -> Nullability {
+        Nullability::from_attributes(&self.attributes)
+    }
 }
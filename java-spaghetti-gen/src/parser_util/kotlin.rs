@@ -0,0 +1,843 @@
+//! Reader for the `kotlin.Metadata` annotation of classes compiled by Kotlin.
+//!
+//! The annotation holds a protobuf message (`d1`) describing the Kotlin declarations, with most strings stored
+//! separately (`d2`), see `metadata.proto` and `jvm_metadata.proto` in the Kotlin repository. Only the parts needed
+//! to map Kotlin declarations to JVM members are decoded, malformed metadata is treated as missing.
+
+use cafebabe::attributes::{AnnotationElementValue, AttributeData};
+use cafebabe::descriptors::FieldType;
+
+use super::{Id, JavaClass, Nullability};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KotlinClassKind {
+    Class,
+    Interface,
+    EnumClass,
+    EnumEntry,
+    AnnotationClass,
+    /// `object` declarations, with a static `INSTANCE` field.
+    Object,
+    /// `companion object` declarations, with a static field in the outer class.
+    CompanionObject,
+    /// Top-level declarations of a file, compiled to static members of a class like `FooKt`.
+    File,
+    /// Classes without declarations in the metadata, like lambdas, `DefaultImpls` or facades of multifile classes.
+    Synthetic,
+}
+
+/// Visibility of a Kotlin declaration, which is public in bytecode for `internal` ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KotlinVisibility {
+    Internal,
+    Private,
+    Protected,
+    Public,
+    PrivateToThis,
+    Local,
+}
+
+impl KotlinVisibility {
+    fn from_flags(flags: u64) -> Self {
+        match (flags >> 1) & 7 {
+            0 => Self::Internal,
+            1 => Self::Private,
+            2 => Self::Protected,
+            3 => Self::Public,
+            4 => Self::PrivateToThis,
+            _ => Self::Local,
+        }
+    }
+
+    /// Returns true for declarations that Kotlin code outside of the module can use.
+    pub fn is_api(self) -> bool {
+        matches!(self, Self::Public | Self::Protected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KotlinAccessor {
+    /// Getter of the named property.
+    Getter(String),
+    /// Setter of the named property.
+    Setter(String),
+}
+
+/// A JVM method declared by Kotlin code: a function, constructor or property accessor.
+#[derive(Debug, Clone)]
+pub struct KotlinMethod {
+    /// JVM name, `<init>` for constructors.
+    pub jvm_name: String,
+    /// JVM descriptor, only kept in the metadata where it can't be derived from the Kotlin types.
+    pub jvm_descriptor: Option<String>,
+    pub visibility: KotlinVisibility,
+    /// Nullability of the JVM parameters, including receivers.
+    pub params: Vec<Nullability>,
+    pub returns: Nullability,
+    pub accessor: Option<KotlinAccessor>,
+}
+
+/// A JVM field declared by Kotlin code, like the backing field of a `const`, `lateinit` or `@JvmField` property.
+#[derive(Debug, Clone)]
+pub struct KotlinField {
+    pub jvm_name: String,
+    pub jvm_descriptor: Option<String>,
+    pub visibility: KotlinVisibility,
+    pub nullability: Nullability,
+}
+
+#[derive(Debug, Clone)]
+pub struct KotlinMetadata {
+    pub kind: KotlinClassKind,
+    /// Simple name of the companion object, usually `Companion`.
+    pub companion_object: Option<String>,
+    pub methods: Vec<KotlinMethod>,
+    pub fields: Vec<KotlinField>,
+}
+
+impl KotlinMetadata {
+    /// Reads the `kotlin.Metadata` annotation of the class, if present and valid.
+    pub fn read(class: &JavaClass) -> Option<Self> {
+        let annotation = class.get().attributes.iter().find_map(|attr| match &attr.data {
+            AttributeData::RuntimeVisibleAnnotations(annotations) => annotations.iter().find(|a| {
+                matches!(&a.type_descriptor.field_type, FieldType::Object(class) if Id::from(class).as_str() == "kotlin/Metadata")
+            }),
+            _ => None,
+        })?;
+
+        let mut kind = 1;
+        let mut d1 = Vec::new();
+        let mut d2 = Vec::new();
+        for element in &annotation.elements {
+            match (element.name.as_ref(), &element.value) {
+                ("k", AnnotationElementValue::IntConstant(k)) => kind = *k,
+                ("d1", AnnotationElementValue::ArrayValue(values)) => d1 = strings(values)?,
+                ("d2", AnnotationElementValue::ArrayValue(values)) => d2 = strings(values)?,
+                _ => {}
+            }
+        }
+        Self::decode(kind, &d1, &d2)
+    }
+
+    /// Decodes the metadata from the values of the `k`, `d1` and `d2` elements of the annotation.
+    pub fn decode(kind: i32, d1: &[&str], d2: &[&str]) -> Option<Self> {
+        let mut metadata = Self {
+            kind: KotlinClassKind::Synthetic,
+            companion_object: None,
+            methods: Vec::new(),
+            fields: Vec::new(),
+        };
+        if !matches!(kind, 1 | 2 | 5) {
+            return Some(metadata);
+        }
+
+        let bytes = decode_bytes(d1)?;
+        let mut input = Message::new(&bytes);
+        let string_table = input.bytes()?;
+        let strings = StringTable::new(string_table, d2)?;
+        let message = input.rest();
+
+        let (functions, properties, constructors, type_table) = if kind == 1 {
+            let mut flags = 6;
+            let (mut functions, mut properties, mut constructors) = (Vec::new(), Vec::new(), Vec::new());
+            let mut type_table = None;
+            for field in Message::new(message) {
+                match field? {
+                    (1, Value::Varint(v)) => flags = v,
+                    (4, Value::Varint(v)) => metadata.companion_object = Some(strings.get(v)?),
+                    (8, Value::Bytes(b)) => constructors.push(b),
+                    (9, Value::Bytes(b)) => functions.push(b),
+                    (10, Value::Bytes(b)) => properties.push(b),
+                    (30, Value::Bytes(b)) => type_table = Some(b),
+                    _ => {}
+                }
+            }
+            metadata.kind = match (flags >> 6) & 7 {
+                0 => KotlinClassKind::Class,
+                1 => KotlinClassKind::Interface,
+                2 => KotlinClassKind::EnumClass,
+                3 => KotlinClassKind::EnumEntry,
+                4 => KotlinClassKind::AnnotationClass,
+                5 => KotlinClassKind::Object,
+                _ => KotlinClassKind::CompanionObject,
+            };
+            (functions, properties, constructors, type_table)
+        } else {
+            let (mut functions, mut properties) = (Vec::new(), Vec::new());
+            let mut type_table = None;
+            for field in Message::new(message) {
+                match field? {
+                    (3, Value::Bytes(b)) => functions.push(b),
+                    (4, Value::Bytes(b)) => properties.push(b),
+                    (30, Value::Bytes(b)) => type_table = Some(b),
+                    _ => {}
+                }
+            }
+            metadata.kind = KotlinClassKind::File;
+            (functions, properties, Vec::new(), type_table)
+        };
+
+        let types = TypeTable::new(type_table)?;
+        for constructor in constructors {
+            metadata
+                .methods
+                .push(decode_constructor(constructor, &strings, &types)?);
+        }
+        for function in functions {
+            metadata.methods.push(decode_function(function, &strings, &types)?);
+        }
+        for property in properties {
+            decode_property(property, &strings, &types, &mut metadata)?;
+        }
+        Some(metadata)
+    }
+
+    /// Finds the declaration of a JVM method. Without a descriptor in the metadata, methods are told apart by the
+    /// number of parameters, ambiguous matches are ignored.
+    pub fn method(&self, name: &str, descriptor: &str, param_count: usize) -> Option<&KotlinMethod> {
+        let mut found = self.methods.iter().filter(|m| {
+            m.jvm_name == name
+                && match &m.jvm_descriptor {
+                    Some(desc) => desc == descriptor,
+                    // Constructors of inner classes take the outer instance first.
+                    None if name == "<init>" => m.params.len() <= param_count,
+                    None => m.params.len() == param_count,
+                }
+        });
+        let method = found.next()?;
+        found.next().is_none().then_some(method)
+    }
+
+    pub fn field(&self, name: &str, descriptor: &str) -> Option<&KotlinField> {
+        self.fields
+            .iter()
+            .find(|f| f.jvm_name == name && f.jvm_descriptor.as_deref().is_none_or(|desc| desc == descriptor))
+    }
+}
+
+fn strings<'a>(values: &'a [AnnotationElementValue]) -> Option<Vec<&'a str>> {
+    values
+        .iter()
+        .map(|value| match value {
+            AnnotationElementValue::StringConstant(s) => Some(s.as_ref()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Decodes `d1`, which stores bytes as the chars of the strings, or in older versions packs 7 bits per char.
+fn decode_bytes(d1: &[&str]) -> Option<Vec<u8>> {
+    let mut chars = d1.iter().flat_map(|s| s.chars()).peekable();
+    if chars.peek() == Some(&'\0') {
+        chars.next();
+        return chars.map(|c| u8::try_from(u32::from(c)).ok()).collect();
+    }
+    if chars.peek() == Some(&'\u{ffff}') {
+        chars.next();
+    }
+    let packed: Vec<u8> = chars
+        .map(|c| u8::try_from(u32::from(c)).ok().map(|b| b.wrapping_add(0x7f) & 0x7f))
+        .collect::<Option<_>>()?;
+    let mut bytes = Vec::with_capacity(packed.len() * 7 / 8);
+    let (mut index, mut bit) = (0, 0);
+    for _ in 0..packed.len() * 7 / 8 {
+        let low = packed[index] >> bit;
+        index += 1;
+        let high = (packed[index] & ((1 << (bit + 1)) - 1)) << (7 - bit);
+        bytes.push(low | high);
+        if bit == 6 {
+            index += 1;
+            bit = 0;
+        } else {
+            bit += 1;
+        }
+    }
+    Some(bytes)
+}
+
+fn decode_function(data: &[u8], strings: &StringTable, class_types: &TypeTable) -> Option<KotlinMethod> {
+    let mut flags = 6;
+    let mut name = None;
+    let (mut return_type, mut receiver_type) = (None, None);
+    let (mut return_type_id, mut receiver_type_id) = (None, None);
+    let mut context_receivers = 0;
+    let mut value_params = Vec::new();
+    let mut signature = None;
+    let mut type_table = None;
+    for field in Message::new(data) {
+        match field? {
+            (9, Value::Varint(v)) => flags = v,
+            (2, Value::Varint(v)) => name = Some(strings.get(v)?),
+            (3, Value::Bytes(b)) => return_type = Some(b),
+            (7, Value::Varint(v)) => return_type_id = Some(v),
+            (5, Value::Bytes(b)) => receiver_type = Some(b),
+            (8, Value::Varint(v)) => receiver_type_id = Some(v),
+            (10, Value::Bytes(_)) | (11, Value::Varint(_)) => context_receivers += 1,
+            (11, Value::Bytes(b)) => context_receivers += Message::new(b).packed_len()?,
+            (6, Value::Bytes(b)) => value_params.push(b),
+            (30, Value::Bytes(b)) => type_table = Some(b),
+            (100, Value::Bytes(b)) => signature = Some(b),
+            _ => {}
+        }
+    }
+    let name = name?;
+    let local_types = TypeTable::new(type_table)?;
+    let types = if type_table.is_some() {
+        &local_types
+    } else {
+        class_types
+    };
+
+    let (jvm_name, jvm_descriptor) = method_signature(signature, strings)?;
+    let is_suspend = flags & (1 << 13) != 0;
+    let mut params = vec![Nullability::Unknown; context_receivers];
+    if receiver_type.is_some() || receiver_type_id.is_some() {
+        params.push(types.nullability(receiver_type, receiver_type_id)?);
+    }
+    for param in value_params {
+        params.push(decode_value_parameter(param, types)?);
+    }
+    let returns = match is_suspend {
+        false => types.nullability(return_type, return_type_id)?,
+        true => {
+            params.push(Nullability::NonNull); // the continuation
+            Nullability::Unknown
+        }
+    };
+    Some(KotlinMethod {
+        jvm_name: jvm_name.unwrap_or(name),
+        jvm_descriptor,
+        visibility: KotlinVisibility::from_flags(flags),
+        params,
+        returns,
+        accessor: None,
+    })
+}
+
+fn decode_constructor(data: &[u8], strings: &StringTable, types: &TypeTable) -> Option<KotlinMethod> {
+    let mut flags = 6;
+    let mut params = Vec::new();
+    let mut signature = None;
+    for field in Message::new(data) {
+        match field? {
+            (1, Value::Varint(v)) => flags = v,
+            (2, Value::Bytes(b)) => params.push(decode_value_parameter(b, types)?),
+            (100, Value::Bytes(b)) => signature = Some(b),
+            _ => {}
+        }
+    }
+    let (_, jvm_descriptor) = method_signature(signature, strings)?;
+    Some(KotlinMethod {
+        jvm_name: "<init>".to_string(),
+        jvm_descriptor,
+        visibility: KotlinVisibility::from_flags(flags),
+        params,
+        returns: Nullability::NonNull,
+        accessor: None,
+    })
+}
+
+fn decode_value_parameter(data: &[u8], types: &TypeTable) -> Option<Nullability> {
+    let (mut ty, mut type_id) = (None, None);
+    for field in Message::new(data) {
+        match field? {
+            (3, Value::Bytes(b)) => ty = Some(b),
+            (5, Value::Varint(v)) => type_id = Some(v),
+            _ => {}
+        }
+    }
+    types.nullability(ty, type_id)
+}
+
+fn decode_property(data: &[u8], strings: &StringTable, types: &TypeTable, out: &mut KotlinMetadata) -> Option<()> {
+    let mut flags = 518;
+    let mut name = None;
+    let (mut return_type, mut return_type_id) = (None, None);
+    let has_receiver = Message::new(data).any(|f| matches!(f, Some((5 | 10, _))));
+    let mut setter_param = None;
+    let mut setter_flags = None;
+    let mut signature = None;
+    for field in Message::new(data) {
+        match field? {
+            (11, Value::Varint(v)) => flags = v,
+            (2, Value::Varint(v)) => name = Some(strings.get(v)?),
+            (3, Value::Bytes(b)) => return_type = Some(b),
+            (9, Value::Varint(v)) => return_type_id = Some(v),
+            (6, Value::Bytes(b)) => setter_param = Some(b),
+            (8, Value::Varint(v)) => setter_flags = Some(v),
+            (100, Value::Bytes(b)) => signature = Some(b),
+            _ => {}
+        }
+    }
+    let name: String = name?;
+    let visibility = KotlinVisibility::from_flags(flags);
+    let nullability = types.nullability(return_type, return_type_id)?;
+    let receiver = has_receiver.then_some(Nullability::Unknown);
+
+    let (mut field, mut getter, mut setter) = (None, None, None);
+    for entry in Message::new(signature.unwrap_or_default()) {
+        match entry? {
+            (1, Value::Bytes(b)) => field = Some(b),
+            (3, Value::Bytes(b)) => getter = Some(b),
+            (4, Value::Bytes(b)) => setter = Some(b),
+            _ => {}
+        }
+    }
+
+    if let Some(field) = field {
+        let (jvm_name, jvm_descriptor) = method_signature(Some(field), strings)?;
+        out.fields.push(KotlinField {
+            jvm_name: jvm_name.unwrap_or_else(|| name.clone()),
+            jvm_descriptor,
+            visibility,
+            nullability,
+        });
+    }
+    // HAS_GETTER
+    if flags & (1 << 9) != 0 {
+        let (jvm_name, jvm_descriptor) = method_signature(getter, strings)?;
+        out.methods.push(KotlinMethod {
+            jvm_name: jvm_name.unwrap_or_else(|| match name.starts_with("is") {
+                true => name.clone(),
+                false => format!("get{}", capitalize(&name)),
+            }),
+            jvm_descriptor,
+            visibility,
+            params: receiver.into_iter().collect(),
+            returns: nullability,
+            accessor: Some(KotlinAccessor::Getter(name.clone())),
+        });
+    }
+    // HAS_SETTER
+    if flags & (1 << 10) != 0 {
+        let (jvm_name, jvm_descriptor) = method_signature(setter, strings)?;
+        let value = match setter_param {
+            Some(param) => decode_value_parameter(param, types)?,
+            None => nullability,
+        };
+        out.methods.push(KotlinMethod {
+            jvm_name: jvm_name.unwrap_or_else(|| match name.strip_prefix("is") {
+                Some(rest) => format!("set{rest}"),
+                None => format!("set{}", capitalize(&name)),
+            }),
+            jvm_descriptor,
+            visibility: setter_flags.map_or(visibility, KotlinVisibility::from_flags),
+            params: receiver.into_iter().chain([value]).collect(),
+            returns: Nullability::Unknown,
+            accessor: Some(KotlinAccessor::Setter(name)),
+        });
+    }
+    Some(())
+}
+
+/// Decodes a `JvmMethodSignature` or `JvmFieldSignature` into the name and descriptor, if different from the
+/// defaults.
+fn method_signature(data: Option<&[u8]>, strings: &StringTable) -> Option<(Option<String>, Option<String>)> {
+    let (mut name, mut descriptor) = (None, None);
+    for field in Message::new(data.unwrap_or_default()) {
+        match field? {
+            (1, Value::Varint(v)) => name = Some(strings.get(v)?),
+            (2, Value::Varint(v)) => descriptor = Some(strings.get(v)?),
+            _ => {}
+        }
+    }
+    Some((name, descriptor))
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// The `StringTableTypes` message, describing how the strings of `d2` are used.
+struct StringTable<'a> {
+    records: Vec<Record<'a>>,
+    d2: &'a [&'a str],
+}
+
+#[derive(Clone, Copy)]
+struct Record<'a> {
+    /// Index of the string in `d2`.
+    index: usize,
+    string: Option<&'a [u8]>,
+    predefined: bool,
+    operation: u64,
+    substring: Option<&'a [u8]>,
+    replace_char: Option<&'a [u8]>,
+}
+
+impl<'a> StringTable<'a> {
+    fn new(data: &'a [u8], d2: &'a [&'a str]) -> Option<Self> {
+        let mut records = Vec::new();
+        for field in Message::new(data) {
+            let (1, Value::Bytes(record)) = field? else {
+                continue;
+            };
+            let mut range = 1;
+            let mut entry = Record {
+                index: 0,
+                string: None,
+                predefined: false,
+                operation: 0,
+                substring: None,
+                replace_char: None,
+            };
+            for field in Message::new(record) {
+                match field? {
+                    (1, Value::Varint(v)) => range = v,
+                    (2, Value::Varint(_)) => entry.predefined = true,
+                    (3, Value::Varint(v)) => entry.operation = v,
+                    (4, Value::Bytes(b)) => entry.substring = Some(b),
+                    (5, Value::Bytes(b)) => entry.replace_char = Some(b),
+                    (6, Value::Bytes(b)) => entry.string = Some(b),
+                    _ => {}
+                }
+            }
+            for _ in 0..range.min(d2.len() as u64 + 1) {
+                entry.index = records.len();
+                records.push(entry);
+            }
+        }
+        Some(Self { records, d2 })
+    }
+
+    /// Returns the string at `index`. Predefined strings are only used for class names, which aren't needed.
+    fn get(&self, index: u64) -> Option<String> {
+        let index = usize::try_from(index).ok()?;
+        let Some(record) = self.records.get(index) else {
+            return self.d2.get(index).map(|s| s.to_string());
+        };
+        if record.predefined {
+            return None;
+        }
+        let mut s = match record.string {
+            Some(bytes) => String::from_utf8(bytes.to_vec()).ok()?,
+            None => self.d2.get(record.index)?.to_string(),
+        };
+        if let Some(bounds) = record.substring {
+            let bounds = Message::new(bounds).packed()?;
+            let &[begin, end] = bounds.as_slice() else {
+                return None;
+            };
+            let chars: Vec<char> = s.chars().collect();
+            s = chars.get(begin as usize..end as usize)?.iter().collect();
+        }
+        if let Some(replace) = record.replace_char {
+            let replace = Message::new(replace).packed()?;
+            let &[from, to] = replace.as_slice() else {
+                return None;
+            };
+            let (from, to) = (char::from_u32(from as u32)?, char::from_u32(to as u32)?);
+            s = s.replace(from, &to.to_string());
+        }
+        match record.operation {
+            1 => s = s.replace('$', "."),
+            2 => s = s.get(1..s.len().checked_sub(1)?)?.replace('$', "."),
+            _ => {}
+        }
+        Some(s)
+    }
+}
+
+/// A `TypeTable`, which types may be referred to by index instead of being inlined.
+struct TypeTable<'a> {
+    types: Vec<&'a [u8]>,
+    first_nullable: Option<u64>,
+}
+
+impl<'a> TypeTable<'a> {
+    fn new(data: Option<&'a [u8]>) -> Option<Self> {
+        let mut table = Self {
+            types: Vec::new(),
+            first_nullable: None,
+        };
+        for field in Message::new(data.unwrap_or_default()) {
+            match field? {
+                (1, Value::Bytes(b)) => table.types.push(b),
+                (2, Value::Varint(v)) => table.first_nullable = Some(v),
+                _ => {}
+            }
+        }
+        Some(table)
+    }
+
+    /// Returns the nullability of a type, given inline or by index. Type parameters and platform types are unknown.
+    fn nullability(&self, ty: Option<&[u8]>, id: Option<u64>) -> Option<Nullability> {
+        let (ty, nullable_by_table) = match (ty, id) {
+            (Some(ty), _) => (ty, false),
+            (None, Some(id)) => (
+                *self.types.get(usize::try_from(id).ok()?)?,
+                self.first_nullable.is_some_and(|first| id >= first),
+            ),
+            (None, None) => return Some(Nullability::Unknown),
+        };
+        let mut nullable = nullable_by_table;
+        let mut class = false;
+        let mut flexible = false;
+        for field in Message::new(ty) {
+            match field? {
+                (3, Value::Varint(v)) => nullable |= v != 0,
+                (4 | 5 | 8, _) => flexible = true,
+                (6 | 12, Value::Varint(_)) => class = true,
+                _ => {}
+            }
+        }
+        Some(match (nullable, class && !flexible) {
+            (true, _) => Nullability::Nullable,
+            (false, true) => Nullability::NonNull,
+            (false, false) => Nullability::Unknown,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Iterates over the fields of a protobuf message, yielding `None` for malformed data.
+struct Message<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Message<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.data.get(self.pos)?;
+            self.pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Reads length-delimited bytes.
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let len = usize::try_from(self.varint()?).ok()?;
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn rest(self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    /// Reads the message as the contents of a packed repeated integer field.
+    fn packed(mut self) -> Option<Vec<u64>> {
+        let mut values = Vec::new();
+        while self.pos < self.data.len() {
+            values.push(self.varint()?);
+        }
+        Some(values)
+    }
+
+    fn packed_len(self) -> Option<usize> {
+        self.packed().map(|values| values.len())
+    }
+}
+
+impl<'a> Iterator for Message<'a> {
+    type Item = Option<(u64, Value<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.data.len() {
+            return None;
+        }
+        let field = (|| {
+            let key = self.varint()?;
+            let value = match key & 7 {
+                0 => Value::Varint(self.varint()?),
+                1 => {
+                    self.pos = self.pos.checked_add(8).filter(|&pos| pos <= self.data.len())?;
+                    Value::Fixed
+                }
+                2 => Value::Bytes(self.bytes()?),
+                5 => {
+                    self.pos = self.pos.checked_add(4).filter(|&pos| pos <= self.data.len())?;
+                    Value::Fixed
+                }
+                _ => return None,
+            };
+            Some((key >> 3, value))
+        })();
+        if field.is_none() {
+            self.pos = self.data.len(); // stop after malformed data
+        }
+        Some(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    /// Encodes a protobuf message from fields holding either an integer or a nested message.
+    fn message(fields: &[(u64, Result<u64, Vec<u8>>)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for (number, value) in fields {
+            match value {
+                Ok(value) => {
+                    varint(number << 3, &mut out);
+                    varint(*value, &mut out);
+                }
+                Err(bytes) => {
+                    varint((number << 3) | 2, &mut out);
+                    varint(bytes.len() as u64, &mut out);
+                    out.extend_from_slice(bytes);
+                }
+            }
+        }
+        out
+    }
+
+    /// A class compiled from `object Registry { fun count(): Int; var name: String?; internal val size: Int }`,
+    /// with the given `d1` element of its metadata.
+    fn registry(d1: &str) -> ClassFile {
+        let metadata = Annotation::new("Lkotlin/Metadata;")
+            .element("k", ElementValue::Int(1))
+            .element("d1", ElementValue::Array(vec![ElementValue::String(d1.to_string())]))
+            .element(
+                "d2",
+                ElementValue::Array(
+                    ["count", "name", "size"]
+                        .map(|s| ElementValue::String(s.to_string()))
+                        .into(),
+                ),
+            );
+        let member = |access, name, descriptor| Member::new(access, name, descriptor);
+        ClassFile::new("com/example/Registry")
+            .annotation(metadata)
+            .field(member(
+                ACC_PUBLIC | ACC_STATIC | ACC_FINAL,
+                "INSTANCE",
+                "Lcom/example/Registry;",
+            ))
+            .method(member(ACC_PUBLIC, "count", "()I"))
+            .method(member(ACC_PUBLIC, "getName", "()Ljava/lang/String;"))
+            .method(member(ACC_PUBLIC, "setName", "(Ljava/lang/String;)V"))
+            .method(member(ACC_PUBLIC, "getSize", "()I"))
+            .method(member(ACC_PUBLIC | ACC_STATIC | ACC_SYNTHETIC, "access$hidden", "()V"))
+    }
+
+    fn registry_metadata() -> String {
+        let non_null = message(&[(6, Ok(0))]);
+        let nullable = message(&[(3, Ok(1)), (6, Ok(0))]);
+        let class = message(&[
+            (1, Ok(5 << 6 | 6)),
+            (9, Err(message(&[(9, Ok(6)), (2, Ok(0)), (3, Err(non_null.clone()))]))),
+            (
+                10,
+                Err(message(&[
+                    (11, Ok(6 | 1 << 9 | 1 << 10)),
+                    (2, Ok(1)),
+                    (3, Err(nullable)),
+                ])),
+            ),
+            (10, Err(message(&[(11, Ok(1 << 9)), (2, Ok(2)), (3, Err(non_null))]))),
+        ]);
+        let mut bytes = vec![0]; // empty string table
+        bytes.extend(class);
+        std::iter::once('\0').chain(bytes.into_iter().map(char::from)).collect()
+    }
+
+    fn generate_registry(yaml: &str, d1: &str) -> String {
+        let mut classes = jdk_classes();
+        classes.push(registry(d1));
+        compact(&generate(yaml, &classes))
+    }
+
+    #[test]
+    fn kotlin_object_members() {
+        let code = generate_registry("", &registry_metadata());
+        let instance_method = |name: &str| format!("pubfn{name}<'env>(self:&::java_spaghetti::Ref<'env,Self>,");
+        assert!(code.contains(&instance_method("count")), "{code}");
+
+        // Property accessors are named after the property, and the nullability of its type is applied.
+        assert!(code.contains(&format!(
+            "{})->::std::result::Result<::std::option::Option<",
+            instance_method("name")
+        )));
+        assert!(code.contains(&instance_method("set_name")));
+        assert!(code.contains("#[doc=\"`arg0`maybenull.\"]"));
+        assert!(!code.contains("pubfngetName"));
+
+        // `internal` declarations and synthetic members are hidden.
+        assert!(!code.contains("getSize"));
+        assert!(!code.contains("hidden"));
+    }
+
+    #[test]
+    fn kotlin_objects_rule() {
+        let yaml = "rules:\n  - match: \"**\"\n    include: true\n    kotlin_objects: true\n";
+        let code = generate_registry(yaml, &registry_metadata());
+        assert!(
+            code.contains("pubfncount<'env>(__jni_env:::java_spaghetti::Env<'env>,)"),
+            "{code}"
+        );
+    }
+
+    #[test]
+    fn malformed_metadata() {
+        // A truncated varint in the length of the string table.
+        let code = generate_registry("", "\0\u{ff}");
+        // The class is generated as if it had no metadata.
+        assert!(code.contains("pubfncount<'env>(self:&::java_spaghetti::Ref<'env,Self>,"));
+        assert!(code.contains("pubfngetSize<'env>"));
+        assert!(code.contains("pubfngetName<'env>"));
+    }
+
+    #[test]
+    fn decode_object_test() {
+        // object Registry { fun count(): Int; fun lookup(key: String): String?; internal var size: Int }
+        let bytes: &[u8] = &[
+            0, 8, 198, 2, 74, 11, 72, 6, 16, 0, 26, 2, 48, 7, 162, 6, 0, 74, 21, 72, 6, 16, 1, 26, 4, 24, 1, 48, 1, 50,
+            6, 16, 2, 26, 2, 48, 1, 162, 6, 0, 82, 16, 88, 128, 14, 16, 3, 26, 2, 48, 7, 162, 6, 4, 10, 2, 8, 3,
+        ];
+        let d1: String = std::iter::once('\0')
+            .chain(bytes.iter().map(|&b| char::from(b)))
+            .collect();
+        let metadata = KotlinMetadata::decode(1, &[&d1], &["count", "lookup", "key", "size"]).unwrap();
+        assert_eq!(metadata.kind, KotlinClassKind::Object);
+
+        let count = metadata.method("count", "()I", 0).unwrap();
+        assert_eq!(count.returns, Nullability::NonNull);
+        let lookup = metadata
+            .method("lookup", "(Ljava/lang/String;)Ljava/lang/String;", 1)
+            .unwrap();
+        assert_eq!(lookup.params, [Nullability::NonNull]);
+        assert_eq!(lookup.returns, Nullability::Nullable);
+
+        let getter = metadata.method("getSize", "()I", 0).unwrap();
+        assert_eq!(getter.accessor, Some(KotlinAccessor::Getter("size".to_string())));
+        assert!(!getter.visibility.is_api());
+        let setter = metadata.method("setSize", "(I)V", 1).unwrap();
+        assert_eq!(setter.accessor, Some(KotlinAccessor::Setter("size".to_string())));
+        assert_eq!(
+            metadata.field("size", "I").unwrap().visibility,
+            KotlinVisibility::Internal
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use cafebabe::MethodAccessFlags;
 use cafebabe::attributes::AttributeData;
-use cafebabe::descriptors::MethodDescriptor;
+use cafebabe::descriptors::{FieldType, MethodDescriptor};
+
+use super::{MethodSignature, Nullability};
 
 pub struct JavaMethod<'a> {
     java: &'a cafebabe::MethodInfo<'a>,
@@ -98,4 +100,118 @@ impl<'a> JavaMethod<'a> {
     pub fn descriptor<'s>(&'s self) -> &'a MethodDescriptor<'a> {
         &self.java.descriptor
     }
+
+    /// Returns the generic signature, if the method has a valid one.
+    pub fn signature(&self) -> Option<MethodSignature> {
+        self.attributes.iter().find_map(|attr| match &attr.data {
+            AttributeData::Signature(s) => MethodSignature::parse(s),
+            _ => None,
+        })
+    }
+
+    pub fn return_nullability(&self) -> Nullability {
+        Nullability::from_attributes(&self.attributes)
+    }
+
+    pub fn parameter_nullability(&self) -> Vec<Nullability> {
+        Nullability::of_parameters(&self.attributes, self.descriptor().parameters.len())
+    }
+
+    /// Returns the source names of the parameters from the `MethodParameters` attribute, or from the debug
+    /// `LocalVariableTable` of the method's code. Returns `None` if either is missing or incomplete.
+    pub fn parameter_names(&self) -> Option<Vec<String>> {
+        let count = self.descriptor().parameters.len();
+        let params = self.attributes.iter().find_map(|attr| match &attr.data {
+            AttributeData::MethodParameters(params) => Some(params),
+            _ => None,
+        });
+        if let Some(params) = params.filter(|params| params.len() == count) {
+            let names: Option<Vec<String>> = params.iter().map(|p| p.name.as_deref().map(str::to_string)).collect();
+            if names.is_some() {
+                return names;
+            }
+        }
+
+        let code = self.attributes.iter().find_map(|attr| match &attr.data {
+            AttributeData::Code(code) => Some(code),
+            _ => None,
+        })?;
+        let locals = code.attributes.iter().find_map(|attr| match &attr.data {
+            AttributeData::LocalVariableTable(locals) => Some(locals),
+            _ => None,
+        })?;
+        // Parameters occupy the first local variable slots after `this`, `long` and `double` take two slots each.
+        let mut slot = if self.is_static() { 0 } else { 1 };
+        let mut names = Vec::with_capacity(count);
+        for param in self.descriptor().parameters.iter() {
+            let local = locals.iter().find(|local| local.index == slot && local.start_pc == 0)?;
+            names.push(local.name.to_string());
+            slot += match (param.dimensions, &param.field_type) {
+                (0, FieldType::Long | FieldType::Double) => 2,
+                _ => 1,
+            };
+        }
+        Some(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser_util::JavaClass;
+    use crate::testing::{ACC_PUBLIC, ACC_STATIC, ClassFile, Member};
+
+    fn parameter_names(method: Member) -> Option<Vec<String>> {
+        let class = JavaClass::read(ClassFile::new("com/example/Foo").method(method).to_bytes()).unwrap();
+        class.methods().map(JavaMethod::from).next().unwrap().parameter_names()
+    }
+
+    #[test]
+    fn local_variable_table() {
+        // `this` takes slot 0, the `long` slots 1 and 2 and the `double` slots 3 and 4.
+        let instance = Member::new(ACC_PUBLIC, "instance", "(JDLjava/lang/String;)V")
+            .local(1, "reused", "I", 1)
+            .local(0, "this", "Lcom/example/Foo;", 0)
+            .local(0, "time", "J", 1)
+            .local(0, "ratio", "D", 3)
+            .local(0, "label", "Ljava/lang/String;", 5)
+            .local(1, "tmp", "I", 6);
+        assert_eq!(parameter_names(instance).unwrap(), ["time", "ratio", "label"]);
+
+        // Arrays of `long` take a single slot.
+        let static_ = Member::new(ACC_PUBLIC | ACC_STATIC, "static_", "(D[JI)V")
+            .local(0, "ratio", "D", 0)
+            .local(0, "times", "[J", 2)
+            .local(0, "count", "I", 3);
+        assert_eq!(parameter_names(static_).unwrap(), ["ratio", "times", "count"]);
+
+        // Only live from the second instruction, so not a parameter.
+        let missing = Member::new(ACC_PUBLIC | ACC_STATIC, "missing", "(I)V").local(1, "count", "I", 0);
+        assert_eq!(parameter_names(missing), None);
+        assert_eq!(parameter_names(Member::new(ACC_PUBLIC, "no_code", "(I)V")), None);
+    }
+
+    #[test]
+    fn method_parameters() {
+        let named = Member::new(ACC_PUBLIC, "named", "(JI)V")
+            .parameter_names(&[Some("time"), Some("count")])
+            .local(0, "this", "Lcom/example/Foo;", 0)
+            .local(0, "a", "J", 1)
+            .local(0, "b", "I", 3);
+        assert_eq!(parameter_names(named).unwrap(), ["time", "count"]);
+
+        // Incomplete attributes fall back to the local variables.
+        let unnamed = Member::new(ACC_PUBLIC | ACC_STATIC, "unnamed", "(JI)V")
+            .parameter_names(&[Some("time"), None])
+            .local(0, "a", "J", 0)
+            .local(0, "b", "I", 2);
+        assert_eq!(parameter_names(unnamed).unwrap(), ["a", "b"]);
+        let short = Member::new(ACC_PUBLIC | ACC_STATIC, "short", "(JI)V")
+            .parameter_names(&[Some("time")])
+            .local(0, "a", "J", 0)
+            .local(0, "b", "I", 2);
+        assert_eq!(parameter_names(short).unwrap(), ["a", "b"]);
+        let only = Member::new(ACC_PUBLIC, "only", "(I)V").parameter_names(&[None]);
+        assert_eq!(parameter_names(only), None);
+    }
 }
@@ -1,9 +1,15 @@
 mod class;
 mod field;
 mod id;
+mod kotlin;
 mod method;
+mod nullability;
+mod signature;
 
 pub use class::JavaClass;
 pub use field::JavaField;
 pub use id::*;
+pub use kotlin::*;
 pub use method::JavaMethod;
+pub use nullability::Nullability;
+pub use signature::*;
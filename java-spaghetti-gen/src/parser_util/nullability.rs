@@ -0,0 +1,256 @@
+//! Nullability of fields, parameters and return values, declared by annotations such as `@NonNull` or `@Nullable`.
+
+use cafebabe::attributes::{
+    Annotation, AnnotationElementValue, AttributeData, AttributeInfo, TypeAnnotation, TypeAnnotationTarget,
+};
+use cafebabe::descriptors::FieldType;
+
+use super::Id;
+
+/// Non-null annotations of common libraries.
+const NON_NULL: &[&str] = &[
+    "android/annotation/NonNull",
+    "android/support/annotation/NonNull",
+    "androidx/annotation/NonNull",
+    "androidx/annotation/RecentlyNonNull",
+    "edu/umd/cs/findbugs/annotations/NonNull",
+    "javax/annotation/Nonnull",
+    "lombok/NonNull",
+    "org/checkerframework/checker/nullness/qual/NonNull",
+    "org/jetbrains/annotations/NotNull",
+    "org/jspecify/annotations/NonNull",
+];
+
+const NULLABLE: &[&str] = &[
+    "android/annotation/Nullable",
+    "android/support/annotation/Nullable",
+    "androidx/annotation/Nullable",
+    "androidx/annotation/RecentlyNullable",
+    "edu/umd/cs/findbugs/annotations/Nullable",
+    "javax/annotation/Nullable",
+    "javax/annotation/CheckForNull",
+    "org/checkerframework/checker/nullness/qual/Nullable",
+    "org/jetbrains/annotations/Nullable",
+    "org/jspecify/annotations/Nullable",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Nullability {
+    #[default]
+    Unknown,
+    NonNull,
+    Nullable,
+}
+
+impl Nullability {
+    /// Reads declaration annotations, visible or not.
+    pub fn from_annotations<'a>(annotations: impl IntoIterator<Item = &'a Annotation<'a>>) -> Self {
+        let mut result = Self::Unknown;
+        for annotation in annotations {
+            let FieldType::Object(class) = &annotation.type_descriptor.field_type else {
+                continue;
+            };
+            let class = Id::from(class);
+            if NULLABLE.contains(&class.as_str()) {
+                // Conflicting annotations are treated as nullable.
+                return Self::Nullable;
+            }
+            if NON_NULL.contains(&class.as_str()) && always(annotation) {
+                result = Self::NonNull;
+            }
+        }
+        result
+    }
+
+    /// Reads declaration annotations of a field or method, and type annotations of the field or return type.
+    pub fn from_attributes(attributes: &[AttributeInfo]) -> Self {
+        let declared = Self::from_annotations(attributes.iter().flat_map(|attr| match &attr.data {
+            AttributeData::RuntimeVisibleAnnotations(a) | AttributeData::RuntimeInvisibleAnnotations(a) => &a[..],
+            _ => &[],
+        }));
+        let type_use = Self::from_annotations(
+            type_annotations(attributes)
+                .filter(|a| matches!(a.target_type, TypeAnnotationTarget::Empty))
+                .map(|a| &a.annotation),
+        );
+        declared.or(type_use)
+    }
+
+    /// Reads parameter annotations and type annotations of parameters, returning `count` values.
+    ///
+    /// Annotations are ignored if their number doesn't match the descriptor, as for synthetic parameters of inner
+    /// class constructors.
+    pub fn of_parameters(attributes: &[AttributeInfo], count: usize) -> Vec<Self> {
+        let mut result = vec![Self::Unknown; count];
+        for attr in attributes {
+            if let AttributeData::RuntimeVisibleParameterAnnotations(params)
+            | AttributeData::RuntimeInvisibleParameterAnnotations(params) = &attr.data
+                && params.len() == count
+            {
+                for (nullability, param) in result.iter_mut().zip(params) {
+                    *nullability = nullability.or(Self::from_annotations(&param.annotations));
+                }
+            }
+        }
+        for annotation in type_annotations(attributes) {
+            if let TypeAnnotationTarget::FormalParameter { index } = annotation.target_type
+                && let Some(nullability) = result.get_mut(index as usize)
+            {
+                *nullability = nullability.or(Self::from_annotations([&annotation.annotation]));
+            }
+        }
+        result
+    }
+
+    /// Combines two sources of nullability, where nullable wins over non-null.
+    pub fn or(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Unknown, other) => other,
+            (_, Self::Nullable) => Self::Nullable,
+            (this, _) => this,
+        }
+    }
+}
+
+/// Type annotations on the outermost type, ignoring those on type arguments, array elements or nested types.
+fn type_annotations<'a>(attributes: &'a [AttributeInfo<'a>]) -> impl Iterator<Item = &'a TypeAnnotation<'a>> {
+    attributes
+        .iter()
+        .flat_map(|attr| match &attr.data {
+            AttributeData::RuntimeVisibleTypeAnnotations(a) | AttributeData::RuntimeInvisibleTypeAnnotations(a) => {
+                &a[..]
+            }
+            _ => &[],
+        })
+        .filter(|a| a.target_path.is_empty())
+}
+
+/// `javax.annotation.Nonnull` has a `when` element, which only means non-null if it is `ALWAYS` (the default).
+fn always(annotation: &Annotation) -> bool {
+    annotation.elements.iter().all(|element| {
+        element.name != "when"
+            || matches!(&element.value, AnnotationElementValue::EnumConstant { const_name, .. } if const_name == "ALWAYS")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser_util::{JavaClass, JavaField, JavaMethod};
+    use crate::testing::{ACC_PUBLIC, Annotation, ClassFile, ElementValue, Member, WithAttributes};
+
+    const STRING: &str = "Ljava/lang/String;";
+    const NON_NULL: &str = "Landroidx/annotation/NonNull;";
+    const NULLABLE: &str = "Lorg/jetbrains/annotations/Nullable;";
+    // Type-use annotations targeting the type of a field, a return type and a parameter.
+    const FIELD: u8 = 0x13;
+    const RETURN: u8 = 0x14;
+    const PARAMETER: u8 = 0x16;
+
+    fn annotation(descriptor: &str) -> Annotation {
+        Annotation::new(descriptor)
+    }
+
+    fn field(name: &str) -> Member {
+        Member::new(ACC_PUBLIC, name, STRING)
+    }
+
+    fn read(class: ClassFile) -> JavaClass {
+        JavaClass::read(class.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn field_annotations() {
+        let javax_nonnull = |when: &str| {
+            annotation("Ljavax/annotation/Nonnull;").element(
+                "when",
+                ElementValue::Enum("Ljavax/annotation/meta/When;".to_string(), when.to_string()),
+            )
+        };
+        let class = read(
+            ClassFile::new("com/example/Fields")
+                .field(field("visible").annotation(annotation(NON_NULL)))
+                // Written by the Android SDK's stubs on recently annotated APIs.
+                .field(
+                    field("recently_non_null")
+                        .invisible_annotation(annotation("Landroidx/annotation/RecentlyNonNull;")),
+                )
+                .field(
+                    field("recently_nullable")
+                        .invisible_annotation(annotation("Landroidx/annotation/RecentlyNullable;")),
+                )
+                .field(field("invisible").invisible_annotation(annotation(NULLABLE)))
+                .field(field("unrelated").annotation(annotation("Ljava/lang/Deprecated;")))
+                .field(field("none"))
+                .field(
+                    field("conflicting")
+                        .annotation(annotation(NON_NULL))
+                        .invisible_annotation(annotation(NULLABLE)),
+                )
+                .field(field("always").annotation(javax_nonnull("ALWAYS")))
+                .field(field("maybe").annotation(javax_nonnull("MAYBE")))
+                .field(field("type_use").type_annotation(FIELD, &[], &[], annotation(NON_NULL)))
+                // `List<@NonNull String>` says nothing about the list.
+                .field(field("type_argument").type_annotation(FIELD, &[], &[3, 0], annotation(NON_NULL))),
+        );
+        let nullability: Vec<_> = (class.fields().map(JavaField::from))
+            .map(|field| (field.name().to_string(), field.nullability()))
+            .collect();
+        let expected = [
+            ("visible", Nullability::NonNull),
+            ("recently_non_null", Nullability::NonNull),
+            ("recently_nullable", Nullability::Nullable),
+            ("invisible", Nullability::Nullable),
+            ("unrelated", Nullability::Unknown),
+            ("none", Nullability::Unknown),
+            ("conflicting", Nullability::Nullable),
+            ("always", Nullability::NonNull),
+            ("maybe", Nullability::Unknown),
+            ("type_use", Nullability::NonNull),
+            ("type_argument", Nullability::Unknown),
+        ];
+        let expected: Vec<_> = (expected.into_iter())
+            .map(|(name, nullability)| (name.to_string(), nullability))
+            .collect();
+        assert_eq!(nullability, expected);
+    }
+
+    #[test]
+    fn method_annotations() {
+        let descriptor = format!("({STRING}{STRING}){STRING}");
+        let method = |name: &str| Member::new(ACC_PUBLIC, name, &descriptor);
+        let class = read(
+            ClassFile::new("com/example/Methods")
+                .method(
+                    method("declared")
+                        .annotation(annotation(NULLABLE))
+                        .parameter_annotations(vec![vec![annotation(NON_NULL)], vec![]]),
+                )
+                .method(
+                    method("type_use")
+                        .type_annotation(RETURN, &[], &[], annotation(NON_NULL))
+                        .type_annotation(PARAMETER, &[1], &[], annotation(NULLABLE))
+                        .type_annotation(PARAMETER, &[0], &[0, 0], annotation(NON_NULL)),
+                )
+                // Annotations of synthetic parameters are left out, so they don't match the descriptor.
+                .method(method("mismatched").parameter_annotations(vec![vec![annotation(NON_NULL)]])),
+        );
+        let methods: Vec<_> = class.methods().map(JavaMethod::from).collect();
+
+        assert_eq!(methods[0].return_nullability(), Nullability::Nullable);
+        assert_eq!(
+            methods[0].parameter_nullability(),
+            [Nullability::NonNull, Nullability::Unknown]
+        );
+        assert_eq!(methods[1].return_nullability(), Nullability::NonNull);
+        assert_eq!(
+            methods[1].parameter_nullability(),
+            [Nullability::Unknown, Nullability::Nullable]
+        );
+        assert_eq!(methods[2].return_nullability(), Nullability::Unknown);
+        assert_eq!(
+            methods[2].parameter_nullability(),
+            [Nullability::Unknown, Nullability::Unknown]
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Parser for generic signatures stored in `Signature` attributes, see
+//! [JVMS 4.7.9.1](https://docs.oracle.com/javase/specs/jvms/se21/html/jvms-4.html#jvms-4.7.9.1).
+//!
+//! Throws clauses are not kept. Malformed signatures are reported as `None`, callers should fall back to descriptors.
+
+use std::collections::HashMap;
+
+use cafebabe::descriptors::{FieldDescriptor, FieldType, MethodDescriptor, ReturnDescriptor};
+
+/// A Java type, possibly with type arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeSignature {
+    /// One of the base type characters `B`, `C`, `D`, `F`, `I`, `J`, `S` or `Z`.
+    Primitive(char),
+    Class(ClassTypeSignature),
+    TypeVariable(String),
+    Array(Box<TypeSignature>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassTypeSignature {
+    /// JNI path of the class, e.g. `java/util/Map$Entry`.
+    pub path: String,
+    /// Type arguments of the innermost class, empty for raw types.
+    pub args: Vec<TypeArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeArgument {
+    /// `?`
+    Any,
+    /// `? extends T`
+    Extends(TypeSignature),
+    /// `? super T`
+    Super(TypeSignature),
+    Exact(TypeSignature),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassSignature {
+    /// Names of the type parameters. Their bounds are not kept, the erased types in descriptors reflect them.
+    pub params: Vec<String>,
+    pub superclass: ClassTypeSignature,
+    pub interfaces: Vec<ClassTypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodSignature {
+    /// Names of the type parameters, see [ClassSignature::params].
+    pub params: Vec<String>,
+    pub args: Vec<TypeSignature>,
+    /// `None` for `void`.
+    pub ret: Option<TypeSignature>,
+}
+
+impl ClassSignature {
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut p = Parser { s, pos: 0 };
+        let params = p.type_params()?;
+        let superclass = p.class_type()?;
+        let mut interfaces = Vec::new();
+        while !p.at_end() {
+            interfaces.push(p.class_type()?);
+        }
+        Some(Self {
+            params,
+            superclass,
+            interfaces,
+        })
+    }
+}
+
+impl MethodSignature {
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut p = Parser { s, pos: 0 };
+        let params = p.type_params()?;
+        p.expect('(')?;
+        let mut args = Vec::new();
+        while !p.eat(')') {
+            args.push(p.java_type()?);
+        }
+        let ret = if p.eat('V') { None } else { Some(p.java_type()?) };
+        while p.eat('^') {
+            p.reference_type()?;
+        }
+        p.at_end().then_some(Self { params, args, ret })
+    }
+
+    /// Builds a signature without type information from a descriptor.
+    pub fn erased(descriptor: &MethodDescriptor) -> Self {
+        Self {
+            params: Vec::new(),
+            args: descriptor.parameters.iter().map(TypeSignature::from).collect(),
+            ret: match &descriptor.return_type {
+                ReturnDescriptor::Return(desc) => Some(desc.into()),
+                ReturnDescriptor::Void => None,
+            },
+        }
+    }
+}
+
+impl TypeSignature {
+    /// Parses a field signature.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut p = Parser { s, pos: 0 };
+        let ty = p.reference_type()?;
+        p.at_end().then_some(ty)
+    }
+
+    /// Returns the innermost element type of arrays, or the type itself.
+    pub fn element(&self) -> &Self {
+        match self {
+            Self::Array(element) => element.element(),
+            ty => ty,
+        }
+    }
+
+    /// Replaces type variables found in `vars`.
+    pub fn substitute(&self, vars: &HashMap<String, TypeSignature>) -> Self {
+        match self {
+            Self::Primitive(c) => Self::Primitive(*c),
+            Self::Class(class) => Self::Class(class.substitute(vars)),
+            Self::TypeVariable(name) => vars.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Self::Array(element) => Self::Array(Box::new(element.substitute(vars))),
+        }
+    }
+}
+
+impl ClassTypeSignature {
+    /// Replaces type variables found in `vars`.
+    pub fn substitute(&self, vars: &HashMap<String, TypeSignature>) -> Self {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                TypeArgument::Any => TypeArgument::Any,
+                TypeArgument::Extends(ty) => TypeArgument::Extends(ty.substitute(vars)),
+                TypeArgument::Super(ty) => TypeArgument::Super(ty.substitute(vars)),
+                TypeArgument::Exact(ty) => TypeArgument::Exact(ty.substitute(vars)),
+            })
+            .collect();
+        Self {
+            path: self.path.clone(),
+            args,
+        }
+    }
+}
+
+impl From<&FieldDescriptor<'_>> for TypeSignature {
+    fn from(descriptor: &FieldDescriptor<'_>) -> Self {
+        let mut ty = match &descriptor.field_type {
+            FieldType::Byte => Self::Primitive('B'),
+            FieldType::Char => Self::Primitive('C'),
+            FieldType::Double => Self::Primitive('D'),
+            FieldType::Float => Self::Primitive('F'),
+            FieldType::Integer => Self::Primitive('I'),
+            FieldType::Long => Self::Primitive('J'),
+            FieldType::Short => Self::Primitive('S'),
+            FieldType::Boolean => Self::Primitive('Z'),
+            FieldType::Object(class) => Self::Class(ClassTypeSignature {
+                path: class.to_string(),
+                args: Vec::new(),
+            }),
+        };
+        for _ in 0..descriptor.dimensions {
+            ty = Self::Array(Box::new(ty));
+        }
+        ty
+    }
+}
+
+struct Parser<'s> {
+    s: &'s str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn at_end(&self) -> bool {
+        self.pos == self.s.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        self.eat(c).then_some(())
+    }
+
+    /// Reads up to (not including) the first of `terminators`.
+    fn identifier(&mut self, terminators: &[char]) -> Option<&str> {
+        let rest = &self.s[self.pos..];
+        let len = rest.find(terminators)?;
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    fn type_params(&mut self) -> Option<Vec<String>> {
+        let mut params = Vec::new();
+        if !self.eat('<') {
+            return Some(params);
+        }
+        while !self.eat('>') {
+            params.push(self.identifier(&[':'])?.to_string());
+            // The class bound may be empty, interface bounds may not.
+            self.expect(':')?;
+            if self.peek() != Some(':') {
+                self.reference_type()?;
+            }
+            while self.eat(':') {
+                self.reference_type()?;
+            }
+        }
+        (!params.is_empty()).then_some(params)
+    }
+
+    fn java_type(&mut self) -> Option<TypeSignature> {
+        match self.peek()? {
+            c @ ('B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z') => {
+                self.pos += 1;
+                Some(TypeSignature::Primitive(c))
+            }
+            _ => self.reference_type(),
+        }
+    }
+
+    fn reference_type(&mut self) -> Option<TypeSignature> {
+        match self.peek()? {
+            'L' => Some(TypeSignature::Class(self.class_type()?)),
+            'T' => {
+                self.pos += 1;
+                let name = self.identifier(&[';'])?.to_string();
+                self.expect(';')?;
+                Some(TypeSignature::TypeVariable(name))
+            }
+            '[' => {
+                self.pos += 1;
+                Some(TypeSignature::Array(Box::new(self.java_type()?)))
+            }
+            _ => None,
+        }
+    }
+
+    fn class_type(&mut self) -> Option<ClassTypeSignature> {
+        self.expect('L')?;
+        let mut path = self.identifier(&['<', '.', ';'])?.to_string();
+        let mut args = self.type_args()?;
+        // Inner classes of generic classes, e.g. `LOuter<TT;>.Inner;`. Only the arguments of the innermost class
+        // are kept.
+        while self.eat('.') {
+            path.push('$');
+            path.push_str(self.identifier(&['<', '.', ';'])?);
+            args = self.type_args()?;
+        }
+        self.expect(';')?;
+        Some(ClassTypeSignature { path, args })
+    }
+
+    fn type_args(&mut self) -> Option<Vec<TypeArgument>> {
+        let mut args = Vec::new();
+        if !self.eat('<') {
+            return Some(args);
+        }
+        while !self.eat('>') {
+            args.push(if self.eat('*') {
+                TypeArgument::Any
+            } else if self.eat('+') {
+                TypeArgument::Extends(self.reference_type()?)
+            } else if self.eat('-') {
+                TypeArgument::Super(self.reference_type()?)
+            } else {
+                TypeArgument::Exact(self.reference_type()?)
+            });
+        }
+        (!args.is_empty()).then_some(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(path: &str, args: Vec<TypeArgument>) -> TypeSignature {
+        TypeSignature::Class(ClassTypeSignature {
+            path: path.to_string(),
+            args,
+        })
+    }
+
+    fn var(name: &str) -> TypeSignature {
+        TypeSignature::TypeVariable(name.to_string())
+    }
+
+    #[test]
+    fn parse_class_signature() {
+        let sig = ClassSignature::parse(
+            "<K:Ljava/lang/Object;V:Ljava/lang/Object;>Ljava/util/AbstractMap<TK;TV;>;Ljava/util/Map<TK;TV;>;",
+        )
+        .unwrap();
+        assert_eq!(sig.params, ["K", "V"]);
+        assert_eq!(sig.superclass.path, "java/util/AbstractMap");
+        assert_eq!(
+            sig.interfaces[0].args,
+            [TypeArgument::Exact(var("K")), TypeArgument::Exact(var("V"))]
+        );
+
+        // Empty class bound followed by an interface bound.
+        let sig = ClassSignature::parse("<E::Ljava/lang/Comparable<-TE;>;>Ljava/lang/Object;").unwrap();
+        assert_eq!(sig.params, ["E"]);
+        assert!(sig.interfaces.is_empty());
+    }
+
+    #[test]
+    fn parse_method_signature() {
+        let sig = MethodSignature::parse(
+            "<T:Ljava/lang/Object;>(Ljava/util/Collection<+TT;>;[TT;I)Ljava/util/List<*>;^Ljava/io/IOException;",
+        )
+        .unwrap();
+        assert_eq!(sig.params, ["T"]);
+        assert_eq!(
+            sig.args,
+            [
+                class("java/util/Collection", vec![TypeArgument::Extends(var("T"))]),
+                TypeSignature::Array(Box::new(var("T"))),
+                TypeSignature::Primitive('I'),
+            ]
+        );
+        assert_eq!(sig.ret, Some(class("java/util/List", vec![TypeArgument::Any])));
+
+        let sig = MethodSignature::parse("(LOuter<TT;>.Inner<Ljava/lang/String;>;)V").unwrap();
+        assert_eq!(
+            sig.args,
+            [class(
+                "Outer$Inner",
+                vec![TypeArgument::Exact(class("java/lang/String", vec![]))]
+            )]
+        );
+        assert_eq!(sig.ret, None);
+
+        assert_eq!(MethodSignature::parse("(Ljava/lang/String"), None);
+        assert_eq!(MethodSignature::parse("()Ljava/util/List<>;"), None);
+    }
+}
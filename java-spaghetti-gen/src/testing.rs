@@ -0,0 +1,532 @@
+//! Helpers for tests: class files assembled in memory, archives and temporary directories.
+
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+
+use crate::config::Config;
+use crate::emit::Context;
+use crate::parser_util::JavaClass;
+
+pub(crate) const ACC_PUBLIC: u16 = 0x0001;
+pub(crate) const ACC_STATIC: u16 = 0x0008;
+pub(crate) const ACC_FINAL: u16 = 0x0010;
+pub(crate) const ACC_BRIDGE: u16 = 0x0040;
+pub(crate) const ACC_VARARGS: u16 = 0x0080;
+pub(crate) const ACC_INTERFACE: u16 = 0x0200;
+pub(crate) const ACC_ABSTRACT: u16 = 0x0400;
+pub(crate) const ACC_SYNTHETIC: u16 = 0x1000;
+pub(crate) const ACC_ENUM: u16 = 0x4000;
+
+/// A class file, without code unless a method has local variables, enough for the generator which only reads
+/// declarations.
+pub(crate) struct ClassFile {
+    access: u16,
+    name: String,
+    super_class: Option<String>,
+    interfaces: Vec<String>,
+    fields: Vec<Member>,
+    methods: Vec<Member>,
+    attributes: Attributes,
+}
+
+/// A field or a method of a [ClassFile].
+pub(crate) struct Member {
+    access: u16,
+    name: String,
+    descriptor: String,
+    attributes: Attributes,
+}
+
+/// An annotation with its type descriptor, e.g. `Landroidx/annotation/NonNull;`.
+pub(crate) struct Annotation {
+    descriptor: String,
+    elements: Vec<(String, ElementValue)>,
+}
+
+pub(crate) enum ElementValue {
+    Int(i32),
+    String(String),
+    /// The type descriptor of the enum and the name of the constant.
+    Enum(String, String),
+    Array(Vec<ElementValue>),
+}
+
+/// Attributes shared by classes and members.
+#[derive(Default)]
+pub(crate) struct Attributes {
+    signature: Option<String>,
+    visible: Vec<Annotation>,
+    invisible: Vec<Annotation>,
+    parameters: Option<Vec<Vec<Annotation>>>,
+    /// Target type, target info, type path and annotation.
+    types: Vec<(u8, Vec<u8>, Vec<u8>, Annotation)>,
+    /// Names of the parameters of a method, `None` for a parameter without a name.
+    parameter_names: Option<Vec<Option<String>>>,
+    /// Start pc, name, descriptor and slot of the local variables in the debug table of a method's code.
+    locals: Option<Vec<(u16, String, String, u16)>>,
+}
+
+/// Setters of [Attributes].
+pub(crate) trait WithAttributes: Sized {
+    fn attributes(&mut self) -> &mut Attributes;
+
+    /// Sets the generic signature, e.g. `(I)TE;` or `<E:Ljava/lang/Object;>Ljava/lang/Object;`.
+    fn signature(mut self, signature: &str) -> Self {
+        self.attributes().signature = Some(signature.to_string());
+        self
+    }
+
+    /// Adds an annotation with runtime retention.
+    fn annotation(mut self, annotation: Annotation) -> Self {
+        self.attributes().visible.push(annotation);
+        self
+    }
+
+    /// Adds an annotation with class retention.
+    fn invisible_annotation(mut self, annotation: Annotation) -> Self {
+        self.attributes().invisible.push(annotation);
+        self
+    }
+
+    /// Sets the annotations of each parameter of a method.
+    fn parameter_annotations(mut self, annotations: Vec<Vec<Annotation>>) -> Self {
+        self.attributes().parameters = Some(annotations);
+        self
+    }
+
+    /// Adds a type annotation, e.g. with the target type `0x14` of return types and an empty `path`. The path is
+    /// made of pairs of bytes, the kind of step and the index of the type argument.
+    fn type_annotation(mut self, target_type: u8, target_info: &[u8], path: &[u8], annotation: Annotation) -> Self {
+        (self.attributes().types).push((target_type, target_info.to_vec(), path.to_vec(), annotation));
+        self
+    }
+}
+
+impl WithAttributes for ClassFile {
+    fn attributes(&mut self) -> &mut Attributes {
+        &mut self.attributes
+    }
+}
+
+impl WithAttributes for Member {
+    fn attributes(&mut self) -> &mut Attributes {
+        &mut self.attributes
+    }
+}
+
+impl Annotation {
+    pub fn new(descriptor: &str) -> Self {
+        Self {
+            descriptor: descriptor.to_string(),
+            elements: Vec::new(),
+        }
+    }
+
+    pub fn element(mut self, name: &str, value: ElementValue) -> Self {
+        self.elements.push((name.to_string(), value));
+        self
+    }
+
+    fn write(&self, pool: &mut ConstantPool, out: &mut Vec<u8>) {
+        out.extend_from_slice(&pool.utf8(&self.descriptor).to_be_bytes());
+        out.extend_from_slice(&(self.elements.len() as u16).to_be_bytes());
+        for (name, value) in &self.elements {
+            out.extend_from_slice(&pool.utf8(name).to_be_bytes());
+            value.write(pool, out);
+        }
+    }
+}
+
+impl ElementValue {
+    fn write(&self, pool: &mut ConstantPool, out: &mut Vec<u8>) {
+        match self {
+            Self::Int(value) => {
+                out.push(b'I');
+                out.extend_from_slice(&pool.int(*value).to_be_bytes());
+            }
+            Self::String(value) => {
+                out.push(b's');
+                out.extend_from_slice(&pool.utf8(value).to_be_bytes());
+            }
+            Self::Enum(descriptor, name) => {
+                out.push(b'e');
+                out.extend_from_slice(&pool.utf8(descriptor).to_be_bytes());
+                out.extend_from_slice(&pool.utf8(name).to_be_bytes());
+            }
+            Self::Array(values) => {
+                out.push(b'[');
+                out.extend_from_slice(&(values.len() as u16).to_be_bytes());
+                for value in values {
+                    value.write(pool, out);
+                }
+            }
+        }
+    }
+}
+
+fn write_annotations(pool: &mut ConstantPool, annotations: &[Annotation]) -> Vec<u8> {
+    let mut out = (annotations.len() as u16).to_be_bytes().to_vec();
+    for annotation in annotations {
+        annotation.write(pool, &mut out);
+    }
+    out
+}
+
+impl Attributes {
+    fn write(&self, pool: &mut ConstantPool, out: &mut Vec<u8>) {
+        let mut attributes: Vec<(&str, Vec<u8>)> = Vec::new();
+        if let Some(signature) = &self.signature {
+            attributes.push(("Signature", pool.utf8(signature).to_be_bytes().to_vec()));
+        }
+        if !self.visible.is_empty() {
+            attributes.push(("RuntimeVisibleAnnotations", write_annotations(pool, &self.visible)));
+        }
+        if !self.invisible.is_empty() {
+            attributes.push(("RuntimeInvisibleAnnotations", write_annotations(pool, &self.invisible)));
+        }
+        if let Some(parameters) = &self.parameters {
+            let mut info = vec![parameters.len() as u8];
+            for annotations in parameters {
+                info.extend(write_annotations(pool, annotations));
+            }
+            attributes.push(("RuntimeInvisibleParameterAnnotations", info));
+        }
+        if !self.types.is_empty() {
+            let mut info = (self.types.len() as u16).to_be_bytes().to_vec();
+            for (target_type, target_info, path, annotation) in &self.types {
+                info.push(*target_type);
+                info.extend_from_slice(target_info);
+                info.push((path.len() / 2) as u8);
+                info.extend_from_slice(path);
+                annotation.write(pool, &mut info);
+            }
+            attributes.push(("RuntimeVisibleTypeAnnotations", info));
+        }
+        if let Some(names) = &self.parameter_names {
+            let mut info = vec![names.len() as u8];
+            for name in names {
+                let name = name.as_deref().map_or(0, |name| pool.utf8(name));
+                info.extend_from_slice(&name.to_be_bytes());
+                info.extend_from_slice(&0_u16.to_be_bytes());
+            }
+            attributes.push(("MethodParameters", info));
+        }
+        if let Some(locals) = &self.locals {
+            attributes.push(("Code", write_code(pool, locals)));
+        }
+        write_attributes(pool, attributes, out);
+    }
+}
+
+/// Writes the `Code` attribute of a method made of `nop` and `return`, with a `LocalVariableTable`.
+fn write_code(pool: &mut ConstantPool, locals: &[(u16, String, String, u16)]) -> Vec<u8> {
+    const CODE: [u8; 2] = [0x00, 0xB1];
+
+    let max_locals = locals.iter().map(|(_, _, _, slot)| slot + 2).max().unwrap_or(0);
+    let mut info = Vec::new();
+    for value in [0, max_locals] {
+        info.extend_from_slice(&value.to_be_bytes());
+    }
+    info.extend_from_slice(&(CODE.len() as u32).to_be_bytes());
+    info.extend_from_slice(&CODE);
+    info.extend_from_slice(&0_u16.to_be_bytes());
+
+    let mut table = (locals.len() as u16).to_be_bytes().to_vec();
+    for (start_pc, name, descriptor, slot) in locals {
+        let length = CODE.len() as u16 - start_pc;
+        let name = pool.utf8(name);
+        let descriptor = pool.utf8(descriptor);
+        for value in [*start_pc, length, name, descriptor, *slot] {
+            table.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+    write_attributes(pool, vec![("LocalVariableTable", table)], &mut info);
+    info
+}
+
+fn write_attributes(pool: &mut ConstantPool, attributes: Vec<(&str, Vec<u8>)>, out: &mut Vec<u8>) {
+    out.extend_from_slice(&(attributes.len() as u16).to_be_bytes());
+    for (name, info) in attributes {
+        out.extend_from_slice(&pool.utf8(name).to_be_bytes());
+        out.extend_from_slice(&(info.len() as u32).to_be_bytes());
+        out.extend_from_slice(&info);
+    }
+}
+
+impl Member {
+    pub fn new(access: u16, name: &str, descriptor: &str) -> Self {
+        Self {
+            access,
+            name: name.to_string(),
+            descriptor: descriptor.to_string(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    /// Sets the `MethodParameters` attribute of a method, `None` for a parameter without a name.
+    pub fn parameter_names(mut self, names: &[Option<&str>]) -> Self {
+        self.attributes.parameter_names = Some(names.iter().map(|name| name.map(str::to_string)).collect());
+        self
+    }
+
+    /// Adds a local variable to the debug table of the method's code, live from `start_pc` to the end of the code.
+    pub fn local(mut self, start_pc: u16, name: &str, descriptor: &str, slot: u16) -> Self {
+        let locals = self.attributes.locals.get_or_insert_with(Vec::new);
+        locals.push((start_pc, name.to_string(), descriptor.to_string(), slot));
+        self
+    }
+
+    fn write(&self, pool: &mut ConstantPool, out: &mut Vec<u8>) {
+        let name = pool.utf8(&self.name);
+        let descriptor = pool.utf8(&self.descriptor);
+        for value in [self.access, name, descriptor] {
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        self.attributes.write(pool, out);
+    }
+}
+
+impl ClassFile {
+    /// A public class extending `java.lang.Object`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            access: ACC_PUBLIC,
+            name: name.to_string(),
+            super_class: (name != "java/lang/Object").then(|| "java/lang/Object".to_string()),
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            attributes: Attributes::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Replaces the access flags, `ACC_PUBLIC` by default.
+    pub fn access(mut self, access: u16) -> Self {
+        self.access = access;
+        self
+    }
+
+    pub fn extends(mut self, super_class: &str) -> Self {
+        self.super_class = Some(super_class.to_string());
+        self
+    }
+
+    pub fn implements(mut self, interface: &str) -> Self {
+        self.interfaces.push(interface.to_string());
+        self
+    }
+
+    pub fn field(mut self, field: Member) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn method(mut self, method: Member) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut pool = ConstantPool::default();
+        let this_class = pool.class(&self.name);
+        let super_class = self.super_class.as_deref().map_or(0, |name| pool.class(name));
+
+        let interfaces: Vec<u16> = self.interfaces.iter().map(|name| pool.class(name)).collect();
+
+        let mut body = Vec::new();
+        for value in [self.access, this_class, super_class, interfaces.len() as u16] {
+            body.extend_from_slice(&value.to_be_bytes());
+        }
+        for interface in interfaces {
+            body.extend_from_slice(&interface.to_be_bytes());
+        }
+        for members in [&self.fields, &self.methods] {
+            body.extend_from_slice(&(members.len() as u16).to_be_bytes());
+            for member in members {
+                member.write(&mut pool, &mut body);
+            }
+        }
+        self.attributes.write(&mut pool, &mut body);
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&0xCAFEBABE_u32.to_be_bytes());
+        out.extend_from_slice(&0_u16.to_be_bytes()); // minor version
+        out.extend_from_slice(&52_u16.to_be_bytes()); // Java 8
+        out.extend_from_slice(&(pool.count + 1).to_be_bytes());
+        out.extend_from_slice(&pool.bytes);
+        out.extend_from_slice(&body);
+        out
+    }
+}
+
+/// The classes of `java.lang` used by the preamble of the generated code.
+pub(crate) fn jdk_classes() -> Vec<ClassFile> {
+    let string = "()Ljava/lang/String;";
+    vec![
+        ClassFile::new("java/lang/Object"),
+        ClassFile::new("java/lang/String"),
+        ClassFile::new("java/lang/Throwable")
+            .method(Member::new(ACC_PUBLIC, "getMessage", string))
+            .method(Member::new(ACC_PUBLIC, "getLocalizedMessage", string))
+            .method(Member::new(
+                ACC_PUBLIC,
+                "getStackTrace",
+                "()[Ljava/lang/StackTraceElement;",
+            )),
+        ClassFile::new("java/lang/StackTraceElement")
+            .method(Member::new(ACC_PUBLIC, "getFileName", string))
+            .method(Member::new(ACC_PUBLIC, "getLineNumber", "()I"))
+            .method(Member::new(ACC_PUBLIC, "getClassName", string))
+            .method(Member::new(ACC_PUBLIC, "getMethodName", string)),
+    ]
+}
+
+/// Builds a crate using the generated files, named by module, and `lib` as the body of its `lib.rs`. Returns the
+/// compiler errors if it fails to build.
+pub(crate) fn check_crate(name: &str, modules: &[(&str, String)], lib: &str) -> Result<(), String> {
+    let dir = TempDir::new(name);
+    let runtime = Path::new(env!("CARGO_MANIFEST_DIR")).join("../java-spaghetti");
+    let manifest = format!(
+        "[package]\nname = \"{name}\"\nversion = \"0.0.0\"\nedition = \"2024\"\n\n\
+         [dependencies]\njava-spaghetti = {{ path = {:?} }}\n\n[workspace]\n",
+        runtime.canonicalize().unwrap()
+    );
+    fs::write(dir.path().join("Cargo.toml"), manifest).unwrap();
+    // The lock file of the workspace pins the dependencies of the runtime, so that no registry update is needed.
+    let lock = Path::new(env!("CARGO_MANIFEST_DIR")).join("../Cargo.lock");
+    fs::copy(lock, dir.path().join("Cargo.lock")).unwrap();
+
+    fs::create_dir(dir.path().join("src")).unwrap();
+    let mut lib_rs = "#![feature(arbitrary_self_types)]\n".to_string();
+    for (module, code) in modules {
+        fs::write(dir.path().join("src").join(format!("{module}.rs")), code).unwrap();
+        lib_rs.push_str(&format!("mod {module};\n"));
+    }
+    lib_rs.push_str(lib);
+    fs::write(dir.path().join("src/lib.rs"), lib_rs).unwrap();
+
+    // Shares the build of the runtime between runs.
+    let target = Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/check-crate");
+    let output = std::process::Command::new(env!("CARGO"))
+        .arg("check")
+        .arg("--quiet")
+        .current_dir(dir.path())
+        .env("CARGO_TARGET_DIR", target)
+        .output()
+        .unwrap();
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(String::from_utf8_lossy(&output.stderr).into_owned()),
+    }
+}
+
+/// Reads a YAML configuration without `input` and `output`.
+pub(crate) fn config(yaml: &str) -> Config {
+    Config::read_str(&format!("input: []\noutput: out.rs\n{yaml}"), Path::new(".")).unwrap()
+}
+
+/// Adds the classes to a new context, as if they were read from the inputs.
+pub(crate) fn context<'a>(config: &'a Config, classes: &[ClassFile]) -> Context<'a> {
+    let mut context = Context::new(config);
+    for class in classes {
+        context.add_class(JavaClass::read(class.to_bytes()).unwrap()).unwrap();
+    }
+    context.include_dependencies().unwrap();
+    context
+}
+
+/// Generates bindings for the classes with the YAML configuration (without `input` and `output`).
+pub(crate) fn generate(yaml: &str, classes: &[ClassFile]) -> String {
+    let config = config(yaml);
+    let mut out = Vec::new();
+    context(&config, classes).write(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+/// Removes all whitespace, to compare generated code regardless of formatting.
+pub(crate) fn compact(code: &str) -> String {
+    code.split_whitespace().collect()
+}
+
+#[derive(Default)]
+struct ConstantPool {
+    bytes: Vec<u8>,
+    count: u16,
+    utf8: Vec<(String, u16)>,
+}
+
+impl ConstantPool {
+    /// Adds a string in the "modified UTF-8" of class files, which encodes `\0` as two bytes.
+    fn utf8(&mut self, value: &str) -> u16 {
+        if let Some(&(_, index)) = self.utf8.iter().find(|(v, _)| v == value) {
+            return index;
+        }
+        let mut bytes = Vec::new();
+        for c in value.chars() {
+            match c {
+                '\0' => bytes.extend_from_slice(&[0xC0, 0x80]),
+                c => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
+        }
+        self.bytes.push(1);
+        self.bytes.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+        self.bytes.extend_from_slice(&bytes);
+        self.count += 1;
+        self.utf8.push((value.to_string(), self.count));
+        self.count
+    }
+
+    fn int(&mut self, value: i32) -> u16 {
+        self.bytes.push(3);
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+        self.count += 1;
+        self.count
+    }
+
+    fn class(&mut self, name: &str) -> u16 {
+        let name = self.utf8(name);
+        self.bytes.push(7);
+        self.bytes.extend_from_slice(&name.to_be_bytes());
+        self.count += 1;
+        self.count
+    }
+}
+
+/// Builds a zip archive, such as a jar, from file names and contents.
+pub(crate) fn zip(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, content) in entries {
+        zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(content).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// A directory under the system temporary directory, removed when dropped.
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("java-spaghetti-gen-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,328 @@
+//! Reader for the "jimage" container used by the `lib/modules` file of JDK 9+ runtime images.
+//!
+//! Layout reference: `jdk.internal.jimage.BasicImageReader` and `ImageLocation` in the OpenJDK sources.
+
+use std::io;
+
+const MAGIC: u32 = 0xCAFE_DADA;
+const HEADER_SIZE: usize = 7 * 4;
+
+const ATTRIBUTE_END: u8 = 0;
+const ATTRIBUTE_MODULE: u8 = 1;
+const ATTRIBUTE_PARENT: u8 = 2;
+const ATTRIBUTE_BASE: u8 = 3;
+const ATTRIBUTE_EXTENSION: u8 = 4;
+const ATTRIBUTE_OFFSET: u8 = 5;
+const ATTRIBUTE_COMPRESSED: u8 = 6;
+const ATTRIBUTE_UNCOMPRESSED: u8 = 7;
+const ATTRIBUTE_COUNT: usize = 8;
+
+/// A jimage file loaded into memory.
+pub struct JImage {
+    data: Vec<u8>,
+    big_endian: bool,
+    table_length: usize,
+    offsets_start: usize,
+    locations_start: usize,
+    strings_start: usize,
+    index_size: usize,
+}
+
+/// A resource stored in a [JImage].
+pub struct JImageEntry {
+    /// Name of the Java module containing this resource, e.g. `java.base`.
+    pub module: String,
+    /// Path of the resource inside of the module, e.g. `java/lang/Object.class`.
+    pub name: String,
+    offset: usize,
+    compressed_size: usize,
+    uncompressed_size: usize,
+}
+
+impl JImageEntry {
+    /// Whether the resource is compressed, as with `jlink --compress`. Its content cannot be read.
+    pub fn is_compressed(&self) -> bool {
+        self.compressed_size != 0
+    }
+}
+
+impl JImage {
+    pub fn read(data: Vec<u8>) -> io::Result<Self> {
+        if data.len() < HEADER_SIZE {
+            return io_data_err!("jimage file is too small to contain a header");
+        }
+
+        let big_endian = if u32::from_le_bytes(data[0..4].try_into().unwrap()) == MAGIC {
+            false
+        } else if u32::from_be_bytes(data[0..4].try_into().unwrap()) == MAGIC {
+            true
+        } else {
+            return io_data_err!("not a jimage file: bad magic number");
+        };
+
+        let mut image = Self {
+            data,
+            big_endian,
+            table_length: 0,
+            offsets_start: 0,
+            locations_start: 0,
+            strings_start: 0,
+            index_size: 0,
+        };
+
+        let version = image.u32_at(4)?;
+        if version >> 16 != 1 {
+            return io_data_err!("unsupported jimage version {}.{}", version >> 16, version & 0xFFFF);
+        }
+        let table_length = image.u32_at(16)? as usize;
+        let locations_size = image.u32_at(20)? as usize;
+        let strings_size = image.u32_at(24)? as usize;
+
+        image.table_length = table_length;
+        image.offsets_start = HEADER_SIZE + table_length * 4;
+        image.locations_start = image.offsets_start + table_length * 4;
+        image.strings_start = image.locations_start + locations_size;
+        image.index_size = image.strings_start + strings_size;
+        if image.index_size > image.data.len() {
+            return io_data_err!("jimage index exceeds the file size");
+        }
+
+        Ok(image)
+    }
+
+    /// Iterates through all resources in the image.
+    pub fn entries(&self) -> impl Iterator<Item = io::Result<JImageEntry>> + '_ {
+        (0..self.table_length).map(|i| {
+            let location = self.u32_at(self.offsets_start + i * 4)? as usize;
+            self.entry_at(self.locations_start + location)
+        })
+    }
+
+    /// Returns the content of the resource. Compressed resources are not supported, see
+    /// [JImageEntry::is_compressed].
+    pub fn content(&self, entry: &JImageEntry) -> io::Result<&[u8]> {
+        if entry.is_compressed() {
+            return io_data_err!(
+                "compressed jimage resource /{}/{} is not supported",
+                entry.module,
+                entry.name
+            );
+        }
+        let start = self.index_size + entry.offset;
+        self.data
+            .get(start..start + entry.uncompressed_size)
+            .ok_or_else(|| io_data_error!("jimage resource /{}/{} is out of bounds", entry.module, entry.name))
+    }
+
+    fn entry_at(&self, mut pos: usize) -> io::Result<JImageEntry> {
+        let mut attributes = [0u64; ATTRIBUTE_COUNT];
+        loop {
+            let byte = *self.byte_at(pos)?;
+            let kind = byte >> 3;
+            if kind == ATTRIBUTE_END {
+                break;
+            }
+            if kind as usize >= ATTRIBUTE_COUNT {
+                return io_data_err!("invalid jimage location attribute kind {kind}");
+            }
+            let length = (byte & 0x7) as usize + 1;
+            let mut value = 0u64;
+            for i in 0..length {
+                value = (value << 8) | *self.byte_at(pos + 1 + i)? as u64;
+            }
+            attributes[kind as usize] = value;
+            pos += 1 + length;
+        }
+
+        let parent = self.string_at(attributes[ATTRIBUTE_PARENT as usize] as usize)?;
+        let base = self.string_at(attributes[ATTRIBUTE_BASE as usize] as usize)?;
+        let extension = self.string_at(attributes[ATTRIBUTE_EXTENSION as usize] as usize)?;
+
+        let mut name = String::new();
+        if !parent.is_empty() {
+            name.push_str(&parent);
+            name.push('/');
+        }
+        name.push_str(&base);
+        if !extension.is_empty() {
+            name.push('.');
+            name.push_str(&extension);
+        }
+
+        Ok(JImageEntry {
+            module: self
+                .string_at(attributes[ATTRIBUTE_MODULE as usize] as usize)?
+                .into_owned(),
+            name,
+            offset: attributes[ATTRIBUTE_OFFSET as usize] as usize,
+            compressed_size: attributes[ATTRIBUTE_COMPRESSED as usize] as usize,
+            uncompressed_size: attributes[ATTRIBUTE_UNCOMPRESSED as usize] as usize,
+        })
+    }
+
+    fn byte_at(&self, pos: usize) -> io::Result<&u8> {
+        self.data
+            .get(pos)
+            .ok_or_else(|| io_data_error!("unexpected end of jimage file"))
+    }
+
+    fn u32_at(&self, pos: usize) -> io::Result<u32> {
+        let bytes: [u8; 4] = self
+            .data
+            .get(pos..pos + 4)
+            .ok_or_else(|| io_data_error!("unexpected end of jimage file"))?
+            .try_into()
+            .unwrap();
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn string_at(&self, offset: usize) -> io::Result<std::borrow::Cow<'_, str>> {
+        let start = self.strings_start + offset;
+        let strings = self
+            .data
+            .get(start..self.index_size)
+            .ok_or_else(|| io_data_error!("jimage string offset {offset} is out of bounds"))?;
+        let len = strings.iter().position(|&b| b == 0).unwrap_or(strings.len());
+        Ok(String::from_utf8_lossy(&strings[..len]))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A resource of an image built by [build_image].
+    pub(crate) struct Resource<'a> {
+        module: &'a str,
+        name: &'a str,
+        content: &'a [u8],
+        compressed: bool,
+    }
+
+    impl<'a> Resource<'a> {
+        /// A resource of `module` named with its path inside of the module, e.g. `java/lang/Object.class`.
+        pub fn new(module: &'a str, name: &'a str, content: &'a [u8]) -> Self {
+            Self {
+                module,
+                name,
+                content,
+                compressed: false,
+            }
+        }
+
+        /// Marks the resource as compressed, keeping its content as is.
+        pub fn compressed(mut self) -> Self {
+            self.compressed = true;
+            self
+        }
+    }
+
+    /// Builds a little-endian image holding the resources.
+    pub(crate) fn build_image(resources: &[Resource]) -> Vec<u8> {
+        let mut strings = vec![0u8]; // offset 0 is the empty string
+        let mut intern = |s: &str| -> u64 {
+            if s.is_empty() {
+                return 0;
+            }
+            let offset = strings.len() as u64;
+            strings.extend_from_slice(s.as_bytes());
+            strings.push(0);
+            offset
+        };
+
+        let mut locations = vec![ATTRIBUTE_END];
+        let mut offsets = Vec::new();
+        let mut contents = Vec::new();
+        for resource in resources {
+            offsets.push(locations.len() as u32);
+            let (parent, file) = resource.name.rsplit_once('/').unwrap_or(("", resource.name));
+            let (base, extension) = file.rsplit_once('.').unwrap_or((file, ""));
+            let content = resource.content;
+            let compressed = match resource.compressed {
+                true => content.len() as u64,
+                false => 0,
+            };
+            for (kind, value) in [
+                (ATTRIBUTE_MODULE, intern(resource.module)),
+                (ATTRIBUTE_PARENT, intern(parent)),
+                (ATTRIBUTE_BASE, intern(base)),
+                (ATTRIBUTE_EXTENSION, intern(extension)),
+                (ATTRIBUTE_OFFSET, contents.len() as u64),
+                (ATTRIBUTE_COMPRESSED, compressed),
+                (ATTRIBUTE_UNCOMPRESSED, content.len() as u64),
+            ] {
+                locations.push((kind << 3) | 3);
+                locations.extend_from_slice(&(value as u32).to_be_bytes());
+            }
+            locations.push(ATTRIBUTE_END);
+            contents.extend_from_slice(content);
+        }
+
+        let mut image = Vec::new();
+        for value in [
+            MAGIC,
+            1 << 16,
+            0,
+            resources.len() as u32,
+            resources.len() as u32,
+            locations.len() as u32,
+            strings.len() as u32,
+        ] {
+            image.extend_from_slice(&value.to_le_bytes());
+        }
+        image.extend(std::iter::repeat_n(0, resources.len() * 4)); // redirect table, unused here
+        for offset in offsets {
+            image.extend_from_slice(&offset.to_le_bytes());
+        }
+        image.extend_from_slice(&locations);
+        image.extend_from_slice(&strings);
+        image.extend_from_slice(&contents);
+        image
+    }
+
+    #[test]
+    fn jimage_read_entries() {
+        let data = build_image(&[
+            Resource::new("java.base", "java/lang/Object.class", b"object"),
+            Resource::new("java.base", "module-info.class", b"info"),
+            Resource::new("java.sql", "java/sql/Date.class", b"date"),
+        ]);
+        let image = JImage::read(data).unwrap();
+        let entries: Vec<_> = image.entries().map(Result::unwrap).collect();
+
+        let names: Vec<_> = entries.iter().map(|e| (e.module.as_str(), e.name.as_str())).collect();
+        assert_eq!(
+            names,
+            [
+                ("java.base", "java/lang/Object.class"),
+                ("java.base", "module-info.class"),
+                ("java.sql", "java/sql/Date.class"),
+            ]
+        );
+        assert_eq!(image.content(&entries[0]).unwrap(), b"object");
+        assert_eq!(image.content(&entries[2]).unwrap(), b"date");
+    }
+
+    #[test]
+    fn jimage_compressed_entry() {
+        let data = build_image(&[
+            Resource::new("java.base", "java/lang/String.class", b"zipped").compressed(),
+            Resource::new("java.base", "java/lang/Object.class", b"object"),
+        ]);
+        let image = JImage::read(data).unwrap();
+        let entries: Vec<_> = image.entries().map(Result::unwrap).collect();
+        assert!(entries[0].is_compressed());
+        assert!(image.content(&entries[0]).is_err());
+        assert!(!entries[1].is_compressed());
+        assert_eq!(image.content(&entries[1]).unwrap(), b"object");
+    }
+
+    #[test]
+    fn jimage_bad_magic() {
+        assert!(JImage::read(vec![0; HEADER_SIZE]).is_err());
+    }
+}
@@ -13,8 +13,16 @@ macro_rules! io_data_err {
 
 mod difference;
 mod generated_file;
+mod jimage;
 mod progress;
+mod report;
+mod stable_hasher;
 
 pub use difference::Difference;
 pub use generated_file::write_generated;
+pub use jimage::JImage;
+#[cfg(test)]
+pub(crate) use jimage::tests::{Resource as JImageResource, build_image as build_jimage};
 pub use progress::Progress;
+pub use report::{Rejection, Report};
+pub use stable_hasher::StableHasher;
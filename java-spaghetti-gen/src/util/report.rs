@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde_derive::Serialize;
+
+/// Methods, fields and proxies skipped by the generator, grouped by class, classes that are not generated and
+/// classes included as dependencies.
+#[derive(Debug, Default)]
+pub struct Report {
+    classes: BTreeMap<String, Vec<Rejection>>,
+    /// Class not generated => why.
+    rejected: BTreeMap<String, Vec<String>>,
+    /// Class included by `Rule::include_dependencies` => why.
+    included: BTreeMap<String, String>,
+}
+
+/// A member of a class that was not generated.
+#[derive(Debug, Serialize)]
+pub struct Rejection {
+    /// `method`, `field` or `proxy`.
+    pub kind: &'static str,
+    pub name: String,
+    /// JNI descriptor, empty for proxies.
+    pub descriptor: String,
+    /// Java declaration, e.g. `java.lang.String substring(int, int)`.
+    pub signature: String,
+    pub reasons: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    skipped: usize,
+    classes: Vec<JsonClass<'a>>,
+    rejected: Vec<JsonRejected<'a>>,
+    included: Vec<JsonIncluded<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonRejected<'a> {
+    class: &'a str,
+    reasons: &'a [String],
+}
+
+#[derive(Serialize)]
+struct JsonIncluded<'a> {
+    class: &'a str,
+    reason: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonClass<'a> {
+    class: &'a str,
+    skipped: usize,
+    members: &'a [Rejection],
+}
+
+impl Report {
+    /// Records a rejected member of `class` (a JNI path like `java/lang/String`). Repeated reasons are merged.
+    pub fn reject(&mut self, class: &str, mut rejection: Rejection) {
+        let mut reasons = Vec::with_capacity(rejection.reasons.len());
+        for reason in rejection.reasons {
+            if !reasons.contains(&reason) {
+                reasons.push(reason);
+            }
+        }
+        rejection.reasons = reasons;
+        self.classes.entry(class.to_string()).or_default().push(rejection);
+    }
+
+    /// Records why a class is not generated. Repeated reasons are merged.
+    pub fn reject_class(&mut self, class: &str, reason: String) {
+        let reasons = self.rejected.entry(class.to_string()).or_default();
+        if !reasons.contains(&reason) {
+            reasons.push(reason);
+        }
+    }
+
+    /// Records a class included as a dependency, with the reason of the first reference to it.
+    pub fn include(&mut self, class: &str, reason: String) {
+        self.rejected.remove(class);
+        self.included.entry(class.to_string()).or_insert(reason);
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.classes.values().map(Vec::len).sum()
+    }
+
+    pub fn to_json(&self) -> String {
+        let report = JsonReport {
+            skipped: self.skipped(),
+            classes: self
+                .classes
+                .iter()
+                .map(|(class, members)| JsonClass {
+                    class,
+                    skipped: members.len(),
+                    members,
+                })
+                .collect(),
+            rejected: (self.rejected.iter())
+                .map(|(class, reasons)| JsonRejected { class, reasons })
+                .collect(),
+            included: (self.included.iter())
+                .map(|(class, reason)| JsonIncluded { class, reason })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&report).unwrap()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (class, members) in &self.classes {
+            writeln!(out, "{class}: {} skipped", members.len()).unwrap();
+            for member in members {
+                writeln!(out, "    {} {}", member.kind, member.signature).unwrap();
+                for reason in &member.reasons {
+                    writeln!(out, "        - {reason}").unwrap();
+                }
+            }
+        }
+        writeln!(
+            out,
+            "total: {} skipped in {} classes",
+            self.skipped(),
+            self.classes.len()
+        )
+        .unwrap();
+        for (class, reasons) in &self.rejected {
+            writeln!(out, "rejected class {class}").unwrap();
+            for reason in reasons {
+                writeln!(out, "    - {reason}").unwrap();
+            }
+        }
+        for (class, reason) in &self.included {
+            writeln!(out, "included dependency {class}: {reason}").unwrap();
+        }
+        out
+    }
+
+    /// Writes the report as JSON if `path` has a `.json` extension, as plain text otherwise.
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        fs::write(path, if is_json { self.to_json() } else { self.to_text() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejection(name: &str, reasons: Vec<&'static str>) -> Rejection {
+        Rejection {
+            kind: "method",
+            name: name.to_string(),
+            descriptor: "()V".to_string(),
+            signature: format!("void {name}()"),
+            reasons,
+        }
+    }
+
+    #[test]
+    fn report_groups_by_class() {
+        let mut report = Report::default();
+        report.reject("com/example/B", rejection("b", vec!["r1"]));
+        report.reject("com/example/A", rejection("a1", vec!["r1", "r2", "r1"]));
+        report.reject("com/example/A", rejection("a2", vec!["r2"]));
+        report.include("com/example/C", "superclass of com/example/A".to_string());
+        report.include("com/example/C", "superclass of com/example/B".to_string());
+        report.reject_class("com/example/D", "not included by the rules".to_string());
+        report.reject_class(
+            "com/example/D",
+            "replaced by java/lang/Object in signatures".to_string(),
+        );
+        report.reject_class("com/example/D", "not included by the rules".to_string());
+        // Included later as a dependency.
+        report.reject_class("com/example/E", "reference only".to_string());
+        report.include("com/example/E", "interface of com/example/A".to_string());
+
+        assert_eq!(report.skipped(), 3);
+        assert_eq!(
+            report.to_text(),
+            "com/example/A: 2 skipped
+    method void a1()
+        - r1
+        - r2
+    method void a2()
+        - r2
+com/example/B: 1 skipped
+    method void b()
+        - r1
+total: 3 skipped in 2 classes
+rejected class com/example/D
+    - not included by the rules
+    - replaced by java/lang/Object in signatures
+included dependency com/example/C: superclass of com/example/A
+included dependency com/example/E: interface of com/example/A
+"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["skipped"], 3);
+        assert_eq!(json["classes"][0]["class"], "com/example/A");
+        assert_eq!(json["classes"][0]["skipped"], 2);
+        assert_eq!(
+            json["classes"][0]["members"][0]["reasons"],
+            serde_json::json!(["r1", "r2"])
+        );
+        assert_eq!(
+            json["rejected"],
+            serde_json::json!([{
+                "class": "com/example/D",
+                "reasons": ["not included by the rules", "replaced by java/lang/Object in signatures"],
+            }])
+        );
+        assert_eq!(json["included"][0]["class"], "com/example/C");
+        assert_eq!(json["included"][1]["class"], "com/example/E");
+    }
+}
@@ -0,0 +1,24 @@
+use std::hash::Hasher;
+
+/// The 64-bit FNV-1a hash. Unlike `DefaultHasher`, its algorithm is fixed, so hashes stored in the cache by one build
+/// of the generator can be compared by another.
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
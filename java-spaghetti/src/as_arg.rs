@@ -85,3 +85,53 @@ unsafe impl<T: ReferenceType, U: AssignableTo<T>> AsArg<T> for Option<&Global<U>
         self.map(|r| r.as_raw()).unwrap_or(null_mut())
     }
 }
+
+/// The Java type of a reference passed as an argument, which selects the overload taking exactly that type in
+/// generated overload sets, e.g. `sb.append((&string,))`. `null` is passed as `None::<&Ref<T>>`.
+pub trait ArgType {
+    type Type: ReferenceType;
+}
+
+impl<U: ArgType> ArgType for &U {
+    type Type = U::Type;
+}
+
+impl<U: ArgType> ArgType for &mut U {
+    type Type = U::Type;
+}
+
+impl<U: ReferenceType> ArgType for Ref<'_, U> {
+    type Type = U;
+}
+
+impl<U: ReferenceType> ArgType for Option<Ref<'_, U>> {
+    type Type = U;
+}
+
+impl<U: ReferenceType> ArgType for Option<&Ref<'_, U>> {
+    type Type = U;
+}
+
+impl<U: ReferenceType> ArgType for Local<'_, U> {
+    type Type = U;
+}
+
+impl<U: ReferenceType> ArgType for Option<Local<'_, U>> {
+    type Type = U;
+}
+
+impl<U: ReferenceType> ArgType for Option<&Local<'_, U>> {
+    type Type = U;
+}
+
+impl<U: ReferenceType> ArgType for Global<U> {
+    type Type = U;
+}
+
+impl<U: ReferenceType> ArgType for Option<Global<U>> {
+    type Type = U;
+}
+
+impl<U: ReferenceType> ArgType for Option<&Global<U>> {
+    type Type = U;
+}
@@ -1,4 +1,4 @@
-use std::ffi::{CStr, c_char, c_void};
+use std::ffi::{CStr, CString, c_char, c_void};
 use std::marker::PhantomData;
 use std::ptr::{self, null_mut};
 use std::sync::OnceLock;
@@ -172,6 +172,21 @@ impl<'env> Env<'env> {
         assert_eq!(res, 0);
     }
 
+    /// Returns a new exception of the JNI class with the message, or the exception thrown while creating it.
+    unsafe fn new_exception<E: ThrowableType>(self, class: &CStr, message: &str) -> Local<'env, E> {
+        let message = CString::new(message.replace('\0', "")).unwrap();
+        let class = ((**self.env).v1_2.FindClass)(self.env, class.as_ptr());
+        if !class.is_null() {
+            ((**self.env).v1_2.ThrowNew)(self.env, class, message.as_ptr());
+            ((**self.env).v1_2.DeleteLocalRef)(self.env, class);
+        }
+        // JNI leaves an exception pending when `FindClass` or `ThrowNew` fails, e.g. an `OutOfMemoryError`.
+        match self.exception_check() {
+            Err(exception) => exception,
+            Ok(()) => unreachable!("no exception is pending after ThrowNew"),
+        }
+    }
+
     pub(crate) unsafe fn raw_exception_to_string(self, exception: jobject) -> String {
         static METHOD_GET_MESSAGE: OnceLock<JMethodID> = OnceLock::new();
         let throwable_get_message = METHOD_GET_MESSAGE.get_or_init(|| {
@@ -286,6 +301,120 @@ impl<'env> Env<'env> {
         string
     }
 
+    /// Gets the position of an enum constant in its declaration with `Enum.ordinal()`.
+    pub unsafe fn get_enum_ordinal<T: ReferenceType>(self, this: &Ref<'env, T>) -> i32 {
+        static METHOD_ORDINAL: OnceLock<JMethodID> = OnceLock::new();
+        let method = METHOD_ORDINAL.get_or_init(|| {
+            let enum_class = self.require_class_jni(c"java/lang/Enum").unwrap();
+            self.require_method_forced(&enum_class, c"ordinal", c"()I")
+        });
+        let ordinal = ((**self.env).v1_2.CallIntMethod)(self.env, this.as_raw(), method.as_raw());
+        self.exception_check_raw()
+            .expect("exception happened calling Enum.ordinal()");
+        ordinal
+    }
+
+    /// Gets the string representation of an object with `Object.toString()`. Returns "null" if it returned null.
+    pub fn get_object_string<T: ReferenceType, E: ThrowableType>(
+        self,
+        this: &Ref<'env, T>,
+    ) -> Result<String, Local<'env, E>> {
+        static METHOD_TO_STRING: OnceLock<JMethodID> = OnceLock::new();
+        unsafe {
+            let method = METHOD_TO_STRING.get_or_init(|| {
+                let object_class = self.require_class_jni(c"java/lang/Object").unwrap();
+                self.require_method_forced(&object_class, c"toString", c"()Ljava/lang/String;")
+            });
+            let jstring = ((**self.env).v1_2.CallObjectMethod)(self.env, this.as_raw(), method.as_raw());
+            self.exception_check()?;
+            if jstring.is_null() {
+                return Ok("null".to_string());
+            }
+            let string = StringChars::from_env_jstring(self, jstring).to_string_lossy();
+            ((**self.env).v1_2.DeleteLocalRef)(self.env, jstring);
+            Ok(string)
+        }
+    }
+
+    /// Compares two objects with `Object.equals()`.
+    pub fn get_objects_equal<T: ReferenceType, U: ReferenceType, E: ThrowableType>(
+        self,
+        this: &Ref<'env, T>,
+        other: &Ref<'env, U>,
+    ) -> Result<bool, Local<'env, E>> {
+        self.get_objects_equal_raw(this, other)
+            .map_err(|throwable| unsafe { Local::from_raw(self, throwable) })
+    }
+
+    /// The same as `get_objects_equal`, except that it may return an owned raw local reference of the exception.
+    pub(crate) fn get_objects_equal_raw<T: ReferenceType, U: ReferenceType>(
+        self,
+        this: &Ref<'env, T>,
+        other: &Ref<'env, U>,
+    ) -> Result<bool, jthrowable> {
+        static METHOD_EQUALS: OnceLock<JMethodID> = OnceLock::new();
+        unsafe {
+            let method = METHOD_EQUALS.get_or_init(|| {
+                let object_class = self.require_class_jni(c"java/lang/Object").unwrap();
+                self.require_method_forced(&object_class, c"equals", c"(Ljava/lang/Object;)Z")
+            });
+            let args = [jvalue { l: other.as_raw() }];
+            let equal = ((**self.env).v1_2.CallBooleanMethodA)(self.env, this.as_raw(), method.as_raw(), args.as_ptr());
+            self.exception_check_raw()?;
+            Ok(equal)
+        }
+    }
+
+    /// Gets the hash code of an object with `Object.hashCode()`.
+    pub fn get_object_hash_code<T: ReferenceType, E: ThrowableType>(
+        self,
+        this: &Ref<'env, T>,
+    ) -> Result<i32, Local<'env, E>> {
+        self.get_object_hash_code_raw(this)
+            .map_err(|throwable| unsafe { Local::from_raw(self, throwable) })
+    }
+
+    /// The same as `get_object_hash_code`, except that it may return an owned raw local reference of the exception.
+    pub(crate) fn get_object_hash_code_raw<T: ReferenceType>(self, this: &Ref<'env, T>) -> Result<i32, jthrowable> {
+        static METHOD_HASH_CODE: OnceLock<JMethodID> = OnceLock::new();
+        unsafe {
+            let method = METHOD_HASH_CODE.get_or_init(|| {
+                let object_class = self.require_class_jni(c"java/lang/Object").unwrap();
+                self.require_method_forced(&object_class, c"hashCode", c"()I")
+            });
+            let hash_code = ((**self.env).v1_2.CallIntMethod)(self.env, this.as_raw(), method.as_raw());
+            self.exception_check_raw()?;
+            Ok(hash_code)
+        }
+    }
+
+    /// Compares two objects with `Comparable.compareTo()`, `this` must be a `Comparable` accepting `other`.
+    pub unsafe fn get_comparison<T: ReferenceType, U: ReferenceType, E: ThrowableType>(
+        self,
+        this: &Ref<'env, T>,
+        other: &Ref<'env, U>,
+    ) -> Result<i32, Local<'env, E>> {
+        self.get_comparison_raw(this, other)
+            .map_err(|throwable| Local::from_raw(self, throwable))
+    }
+
+    /// The same as `get_comparison`, except that it may return an owned raw local reference of the exception.
+    pub(crate) unsafe fn get_comparison_raw<T: ReferenceType, U: ReferenceType>(
+        self,
+        this: &Ref<'env, T>,
+        other: &Ref<'env, U>,
+    ) -> Result<i32, jthrowable> {
+        static METHOD_COMPARE_TO: OnceLock<JMethodID> = OnceLock::new();
+        let method = METHOD_COMPARE_TO.get_or_init(|| {
+            let comparable_class = self.require_class_jni(c"java/lang/Comparable").unwrap();
+            self.require_method_forced(&comparable_class, c"compareTo", c"(Ljava/lang/Object;)I")
+        });
+        let args = [jvalue { l: other.as_raw() }];
+        let comparison = ((**self.env).v1_2.CallIntMethodA)(self.env, this.as_raw(), method.as_raw(), args.as_ptr());
+        self.exception_check_raw()?;
+        Ok(comparison)
+    }
+
     /// Binds the function pointer to the native method of `class` according to method name and signature.
     /// Returns `false` if the method is not found or the JNI `RegisterNatives` returns a negative value.
     ///
@@ -383,6 +512,74 @@ impl<'env> Env<'env> {
         }
         JFieldID::from_raw(res)
     }
+
+    /// Returns a new `java.lang.IllegalArgumentException` as the error if the argument is not null and not an instance
+    /// of `class`, which is loaded into `cache` on first use. A `java.lang.NoClassDefFoundError` is returned if the
+    /// class cannot be loaded.
+    ///
+    /// Generated bindings call this for arguments declared with a Java type that is replaced by one of its
+    /// superclasses in the Rust signature, because that class is not generated.
+    pub unsafe fn require_arg_instance_of<T: ReferenceType, E: ThrowableType>(
+        self,
+        arg: &impl AsArg<T>,
+        class: &CStr,
+        cache: &OnceLock<JClass>,
+    ) -> Result<(), Local<'env, E>> {
+        let object = arg.as_arg();
+        if object.is_null() {
+            return Ok(());
+        }
+        let class_name = class.to_string_lossy().replace('/', ".");
+        let jclass = match cache.get() {
+            Some(jclass) => jclass,
+            None => match self.require_class(class) {
+                Ok(jclass) => cache.get_or_init(|| jclass),
+                Err(err) => return Err(self.new_exception(c"java/lang/NoClassDefFoundError", &err.0)),
+            },
+        };
+        if ((**self.env).v1_2.IsInstanceOf)(self.env, object, jclass.as_raw()) {
+            return Ok(());
+        }
+        let message = format!("argument is not an instance of {class_name}");
+        Err(self.new_exception(c"java/lang/IllegalArgumentException", &message))
+    }
+
+    /// Returns the result, or a new `java.lang.ClassCastException` as the error if the returned object is not an
+    /// instance of `T`.
+    ///
+    /// Generated bindings call this for objects returned as a type parameter of a generic Java class or method,
+    /// because Java only checks them against the erased type.
+    pub unsafe fn require_return_instance_of<T: ReferenceType, E: ThrowableType>(
+        self,
+        result: Result<Option<Local<'env, T>>, Local<'env, E>>,
+    ) -> Result<Option<Local<'env, T>>, Local<'env, E>> {
+        let Some(object) = result? else {
+            return Ok(None);
+        };
+        let class = match T::jni_get_class(self) {
+            Ok(class) => class,
+            Err(err) => return Err(self.new_exception(c"java/lang/NoClassDefFoundError", &err.0)),
+        };
+        if ((**self.env).v1_2.IsInstanceOf)(self.env, object.as_raw(), class.as_raw()) {
+            return Ok(Some(object));
+        }
+        let class_name = T::jni_reference_type_name().to_string_lossy().replace('/', ".");
+        let message = format!("returned object is not an instance of {class_name}");
+        Err(self.new_exception(c"java/lang/ClassCastException", &message))
+    }
+
+    /// Returns the object, or a new `java.lang.NullPointerException` as the error if it is null.
+    ///
+    /// Generated bindings call this for values annotated as non-null, because Java doesn't enforce the annotations.
+    pub unsafe fn require_non_null<T: ReferenceType, E: ThrowableType>(
+        self,
+        result: Result<Option<Local<'env, T>>, Local<'env, E>>,
+    ) -> Result<Local<'env, T>, Local<'env, E>> {
+        if let Some(object) = result? {
+            return Ok(object);
+        }
+        Err(self.new_exception(c"java/lang/NullPointerException", "null value annotated as non-null"))
+    }
 }
 
 macro_rules! call_primitive_method_a {
@@ -18,12 +18,14 @@ pub use ::jni_sys as sys;
 mod refs {
     mod arg;
     mod global;
+    mod key;
     mod local;
     mod ref_;
     mod return_;
 
     pub use arg::*;
     pub use global::*;
+    pub use key::*;
     pub use local::*;
     pub use ref_::*;
     pub use return_::*;
@@ -66,7 +68,7 @@ pub struct ClassLoaderError(String);
 impl std::error::Error for ClassLoaderError {}
 impl fmt::Display for ClassLoaderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "ClassLoader failed: {}", &self.0)
+        writeln!(f, "ClassLoader failed: {}", self.0)
     }
 }
 
@@ -126,6 +128,16 @@ pub trait JavaDebug: ReferenceType {
     fn fmt(self: &Ref<'_, Self>, f: &mut fmt::Formatter<'_>) -> fmt::Result;
 }
 
+/// Marks types compared and hashed by [JavaKey] with `equals()` and `hashCode()`.
+pub trait JavaEq: ReferenceType {}
+
+/// Marks types ordered by [JavaKey] with `compareTo()`.
+///
+/// # Safety
+///
+/// `Self` implements `java.lang.Comparable` accepting instances of `Self`.
+pub unsafe trait JavaOrd: JavaEq {}
+
 /// Represents a Java `null` value.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Null;
@@ -0,0 +1,95 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+use crate::{Global, JavaDebug, JavaDisplay, JavaEq, JavaOrd, ReferenceType};
+
+/// A [Global] reference compared, hashed and ordered as the Java object, with `equals()`, `hashCode()` and
+/// `compareTo()`, so that it can be used as the key of a `HashMap` or a `BTreeMap`.
+///
+/// The methods are enabled for classes implementing [JavaEq] and [JavaOrd], which is configured with `java_eq` in
+/// the rules of `java-spaghetti-gen`. Exceptions thrown by them cause panics.
+pub struct JavaKey<T: ReferenceType>(Global<T>);
+
+impl<T: ReferenceType> JavaKey<T> {
+    pub fn new(global: Global<T>) -> Self {
+        Self(global)
+    }
+
+    pub fn into_inner(self) -> Global<T> {
+        self.0
+    }
+}
+
+impl<T: ReferenceType> From<Global<T>> for JavaKey<T> {
+    fn from(global: Global<T>) -> Self {
+        Self(global)
+    }
+}
+
+impl<T: ReferenceType> Deref for JavaKey<T> {
+    type Target = Global<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ReferenceType> Clone for JavaKey<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+/// Panics if `equals()` throws an exception.
+impl<T: JavaEq> PartialEq for JavaKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.vm().with_env(|env| {
+            env.get_objects_equal_raw(&self.as_ref(env), &other.as_ref(env))
+                .expect("exception happened calling Object.equals()")
+        })
+    }
+}
+
+impl<T: JavaEq> Eq for JavaKey<T> {}
+
+/// Panics if `hashCode()` throws an exception.
+impl<T: JavaEq> Hash for JavaKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let hash_code = self.vm().with_env(|env| {
+            env.get_object_hash_code_raw(&self.as_ref(env))
+                .expect("exception happened calling Object.hashCode()")
+        });
+        state.write_i32(hash_code);
+    }
+}
+
+impl<T: JavaOrd> PartialOrd for JavaKey<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Panics if `compareTo()` throws an exception.
+impl<T: JavaOrd> Ord for JavaKey<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Safety: `JavaOrd` guarantees that `T` is comparable to itself.
+        let comparison = self.vm().with_env(|env| unsafe {
+            env.get_comparison_raw(&self.as_ref(env), &other.as_ref(env))
+                .expect("exception happened calling Comparable.compareTo()")
+        });
+        comparison.cmp(&0)
+    }
+}
+
+impl<T: JavaDebug> Debug for JavaKey<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.vm().with_env(|env| Debug::fmt(&self.as_ref(env), f))
+    }
+}
+
+impl<T: JavaDisplay> Display for JavaKey<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.vm().with_env(|env| Display::fmt(&self.as_ref(env), f))
+    }
+}